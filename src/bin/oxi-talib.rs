@@ -0,0 +1,564 @@
+//! A command-line scanner printing the candlestick patterns found in OHLC data.
+//!
+//! Reads candles from a CSV or JSON file, or from stdin, and prints the detections
+//! as a table, CSV or JSON lines. With `--stream`, runs until the end of the input and
+//! writes the detections of each JSON candle line as soon as it arrives. Run
+//! `oxi-talib --help` for the options.
+
+use clap::{Parser, ValueEnum};
+use oxi_talib::Error::{InvalidCandle, InvalidInput};
+use oxi_talib::{
+    cdl, configure, Column, CsvFormat, CsvReader, DateFormat, Detection, Error, Pattern,
+    Recognizer, Settings, SimpleCandle,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Scans OHLC data for candlestick patterns.
+#[derive(Debug, Parser)]
+#[command(name = "oxi-talib", version)]
+struct Args {
+    /// The input file. Reads stdin if omitted or `-`.
+    input: Option<PathBuf>,
+
+    /// The format of the input. Detected from the file extension if omitted.
+    #[arg(short, long, value_enum)]
+    format: Option<InputFormat>,
+
+    /// The patterns to scan for, by name. Scans all patterns if omitted.
+    #[arg(short, long = "pattern", value_delimiter = ',')]
+    patterns: Vec<Pattern>,
+
+    /// A JSON file with the recognition settings.
+    #[arg(short, long)]
+    settings: Option<PathBuf>,
+
+    /// The format of the output. Ignored in the streaming mode.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    /// Runs until the end of the input, reading one JSON candle per line and writing
+    /// the detections as JSON lines as soon as each candle arrives.
+    #[arg(long)]
+    stream: bool,
+
+    /// The field identifying the instrument of a candle in the streaming mode. Each
+    /// instrument is recognized separately.
+    #[arg(long, default_value = "symbol")]
+    symbol: String,
+
+    #[command(flatten)]
+    columns: Columns,
+}
+
+/// The names of the input fields.
+#[derive(Debug, clap::Args)]
+struct Columns {
+    /// The field of the opening price.
+    #[arg(long, default_value = "open")]
+    open: String,
+
+    /// The field of the highest price.
+    #[arg(long, default_value = "high")]
+    high: String,
+
+    /// The field of the lowest price.
+    #[arg(long, default_value = "low")]
+    low: String,
+
+    /// The field of the closing price.
+    #[arg(long, default_value = "close")]
+    close: String,
+
+    /// The field of the opening time, if any.
+    #[arg(long)]
+    timestamp: Option<String>,
+
+    /// The format of the timestamp field: `unix-ms`, `unix-s`, `rfc3339` or a
+    /// `chrono` format string such as `%Y-%m-%d`.
+    #[arg(long, default_value = "unix-ms", value_parser = parse_date_format)]
+    date_format: DateFormat,
+
+    /// The field of the traded volume, if any.
+    #[arg(long)]
+    volume: Option<String>,
+
+    /// The field delimiter of CSV input, an ASCII character.
+    #[arg(long, default_value = ",", value_parser = parse_delimiter)]
+    delimiter: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// Comma-separated values with a header.
+    Csv,
+    /// A JSON array of objects, or one JSON object per line.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// An aligned text table.
+    Table,
+    /// Comma-separated values with a header.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+/// An error of the scanner.
+#[derive(Debug)]
+enum CliError {
+    /// The data could not be read or the detections could not be written.
+    Io(io::Error),
+    /// The data could not be scanned.
+    Scan(Error),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "I/O error: {e}"),
+            CliError::Scan(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(e: Error) -> Self {
+        CliError::Scan(e)
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("oxi-talib: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), CliError> {
+    if let Some(path) = &args.settings {
+        let file = open(path)?;
+        let settings: Settings =
+            serde_json::from_reader(file).map_err(|e| InvalidInput(e.to_string()))?;
+        configure(settings)?;
+    }
+
+    let patterns = if args.patterns.is_empty() {
+        Pattern::all()
+    } else {
+        &args.patterns
+    };
+
+    let path = args.input.as_deref().filter(|path| *path != Path::new("-"));
+    let input: Box<dyn Read> = match path {
+        Some(path) => Box::new(open(path)?),
+        None => Box::new(io::stdin().lock()),
+    };
+
+    if args.stream {
+        return stream(
+            BufReader::new(input),
+            &mut io::stdout().lock(),
+            &mut io::stderr().lock(),
+            &args,
+            patterns,
+        );
+    }
+
+    let format = args.format.unwrap_or_else(|| {
+        match path.and_then(Path::extension).and_then(|e| e.to_str()) {
+            Some("json" | "jsonl" | "ndjson") => InputFormat::Json,
+            _ => InputFormat::Csv,
+        }
+    });
+    let candles = match format {
+        InputFormat::Csv => {
+            CsvReader::new(input, csv_format(&args.columns))?.collect::<Result<_, _>>()?
+        }
+        InputFormat::Json => read_json(input, &args.columns)?,
+    };
+
+    let detections = cdl().detect_all(patterns, &candles)?;
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    write_detections(&mut out, args.output, &detections)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Opens a file, naming it in the error.
+fn open(path: &Path) -> io::Result<File> {
+    File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
+/// Recognizes the patterns in a stream of JSON lines, one recognizer per symbol.
+///
+/// Invalid lines are reported to `errors` and skipped.
+fn stream(
+    input: impl BufRead,
+    out: &mut impl Write,
+    errors: &mut impl Write,
+    args: &Args,
+    patterns: &[Pattern],
+) -> Result<(), CliError> {
+    let mut recognizers: HashMap<String, Recognizer> = HashMap::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let detections = serde_json::from_str::<Map<String, Value>>(&line)
+            .map_err(|e| e.to_string())
+            .and_then(|object| {
+                let symbol = match object.get(&args.symbol) {
+                    Some(Value::String(symbol)) => symbol.clone(),
+                    Some(value) => value.to_string(),
+                    None => return Err(format!("field \"{}\" is missing", args.symbol)),
+                };
+                let candle = json_candle(&object, &args.columns)?;
+
+                let recognizer = recognizers
+                    .entry(symbol.clone())
+                    .or_insert_with(|| Recognizer::new(cdl(), patterns));
+                let detections = recognizer.push(candle).map_err(|e| e.to_string())?;
+
+                Ok((symbol, detections))
+            });
+
+        match detections {
+            Ok((symbol, detections)) => {
+                for detection in detections {
+                    let mut json = detection_json(&detection);
+                    json["symbol"] = Value::String(symbol.clone());
+                    writeln!(out, "{json}")?;
+                }
+                out.flush()?;
+            }
+            Err(reason) => writeln!(errors, "oxi-talib: line {}: {reason}", index + 1)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn csv_format(columns: &Columns) -> CsvFormat {
+    CsvFormat {
+        open: Column::Name(columns.open.clone()),
+        high: Column::Name(columns.high.clone()),
+        low: Column::Name(columns.low.clone()),
+        close: Column::Name(columns.close.clone()),
+        timestamp: columns.timestamp.clone().map(Column::Name),
+        date_format: columns.date_format.clone(),
+        volume: columns.volume.clone().map(Column::Name),
+        delimiter: columns.delimiter,
+        has_headers: true,
+    }
+}
+
+fn read_json(mut input: impl Read, columns: &Columns) -> Result<Vec<SimpleCandle>, CliError> {
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+
+    let candles: Result<_, Error> = if text.trim_start().starts_with('[') {
+        let values: Vec<Map<String, Value>> =
+            serde_json::from_str(&text).map_err(|e| InvalidInput(e.to_string()))?;
+
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, object)| {
+                json_candle(object, columns)
+                    .map_err(|reason| InvalidCandle(format!("element {index}: {reason}")))
+            })
+            .collect()
+    } else {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let line_number = index + 1;
+                serde_json::from_str(line)
+                    .map_err(|e| e.to_string())
+                    .and_then(|object| json_candle(&object, columns))
+                    .map_err(|reason| InvalidCandle(format!("line {line_number}: {reason}")))
+            })
+            .collect()
+    };
+
+    Ok(candles?)
+}
+
+/// Converts a JSON object into a candle. Prices may be numbers or numeric strings.
+fn json_candle(object: &Map<String, Value>, columns: &Columns) -> Result<SimpleCandle, String> {
+    let field = |name: &str| {
+        object
+            .get(name)
+            .ok_or_else(|| format!("field \"{name}\" is missing"))
+    };
+    let number = |name: &str| {
+        match field(name)? {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("field \"{name}\" is not a number"))
+    };
+
+    let mut candle = SimpleCandle::try_new(
+        number(&columns.open)?,
+        number(&columns.close)?,
+        number(&columns.high)?,
+        number(&columns.low)?,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if let Some(name) = &columns.timestamp {
+        let text = match field(name)? {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        let timestamp = columns
+            .date_format
+            .parse(&text)
+            .ok_or_else(|| format!("\"{text}\" is not a valid date"))?;
+        candle = candle.with_timestamp(timestamp);
+    }
+    if let Some(name) = &columns.volume {
+        candle = candle
+            .try_with_volume(number(name)?)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(candle)
+}
+
+fn write_detections(
+    out: &mut impl Write,
+    format: OutputFormat,
+    detections: &[Detection],
+) -> io::Result<()> {
+    let timestamp = |detection: &Detection| {
+        detection
+            .timestamp
+            .map_or_else(String::new, |timestamp| timestamp.to_string())
+    };
+
+    match format {
+        OutputFormat::Table => {
+            writeln!(
+                out,
+                "{:>8}  {:>13}  {:<24}  {:>7}",
+                "index", "timestamp", "pattern", "quality"
+            )?;
+            for detection in detections {
+                writeln!(
+                    out,
+                    "{:>8}  {:>13}  {:<24}  {:>7}",
+                    detection.index,
+                    timestamp(detection),
+                    pattern_name(detection.pattern),
+                    detection.signal.quality.value()
+                )?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(out, "index,timestamp,pattern,quality")?;
+            for detection in detections {
+                writeln!(
+                    out,
+                    "{},{},{},{}",
+                    detection.index,
+                    timestamp(detection),
+                    pattern_name(detection.pattern),
+                    detection.signal.quality.value()
+                )?;
+            }
+        }
+        OutputFormat::Jsonl => {
+            for detection in detections {
+                writeln!(out, "{}", detection_json(detection))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn detection_json(detection: &Detection) -> Value {
+    let span = detection.signal.span();
+
+    json!({
+        "index": detection.index,
+        "timestamp": detection.timestamp,
+        "pattern": pattern_name(detection.pattern),
+        "quality": detection.signal.quality.value(),
+        "start": span.start,
+        "len": span.len,
+    })
+}
+
+/// Returns the name of a pattern in the output, its variant name like in the JSON
+/// settings and the columns of the library, e.g. `"ThreeWhiteSoldiers"`.
+fn pattern_name(pattern: Pattern) -> String {
+    format!("{pattern:?}")
+}
+
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        &[delimiter] if delimiter.is_ascii() => Ok(delimiter),
+        _ => Err(format!("\"{value}\" is not a single ASCII character")),
+    }
+}
+
+fn parse_date_format(value: &str) -> Result<DateFormat, String> {
+    Ok(match value {
+        "unix-ms" => DateFormat::UnixMillis,
+        "unix-s" => DateFormat::UnixSeconds,
+        "rfc3339" => DateFormat::Rfc3339,
+        format => DateFormat::Custom(format.into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxi_talib::Candle;
+
+    fn args(extra: &[&str]) -> Args {
+        Args::parse_from(["oxi-talib"].iter().chain(extra))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = args(&[
+            "-p",
+            "hammer,Doji",
+            "--pattern",
+            "three white soldiers",
+            "-o",
+            "jsonl",
+        ]);
+
+        assert_eq!(
+            args.patterns,
+            vec![Pattern::Hammer, Pattern::Doji, Pattern::ThreeWhiteSoldiers]
+        );
+        assert_eq!(args.output, OutputFormat::Jsonl);
+        assert_eq!(args.columns.delimiter, b',');
+        assert!(Args::try_parse_from(["oxi-talib", "-p", "abandoned baby"]).is_err());
+        assert_eq!(
+            Args::parse_from(["oxi-talib", "--delimiter", ";"])
+                .columns
+                .delimiter,
+            b';'
+        );
+        assert!(Args::try_parse_from(["oxi-talib", "--delimiter", "§"]).is_err());
+    }
+
+    #[test]
+    fn test_read_json() {
+        let columns = args(&["--timestamp", "time", "--date-format", "%Y-%m-%d"]).columns;
+        let lines = r#"{"time": "2025-09-10", "open": 100, "high": 103, "low": 99, "close": "102"}
+
+{"time": "2025-09-11", "open": 102, "high": 104, "low": 101, "close": 101.5}"#;
+
+        let candles = read_json(lines.as_bytes(), &columns).unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].close(), 102.0);
+        assert_eq!(candles[1].timestamp(), Some(1_757_548_800_000));
+
+        let array = r#"[{"time": "2025-09-10", "open": 100, "high": 98, "low": 99, "close": 99}]"#;
+        assert!(matches!(
+            read_json(array.as_bytes(), &columns),
+            Err(CliError::Scan(InvalidCandle(reason))) if reason.starts_with("element 0:")
+        ));
+    }
+
+    #[test]
+    fn test_stream_keeps_state_per_symbol() {
+        let args = args(&["--stream", "-p", "doji"]);
+        let mut input = String::new();
+        for i in 0..12 {
+            for symbol in ["AAA", "BBB"] {
+                input += &format!(
+                    "{{\"symbol\": \"{symbol}\", \"open\": 10, \"high\": 11, \"low\": 9, \"close\": 10}}\n"
+                );
+            }
+            if i == 5 {
+                input += "not json\n";
+            }
+        }
+
+        let (mut out, mut errors) = (Vec::new(), Vec::new());
+        stream(
+            input.as_bytes(),
+            &mut out,
+            &mut errors,
+            &args,
+            &args.patterns,
+        )
+        .unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["symbol"], "AAA");
+        assert_eq!(lines[0]["index"], 10);
+        assert_eq!(lines[1]["symbol"], "BBB");
+        assert_eq!(lines[1]["index"], 10);
+        assert!(String::from_utf8(errors)
+            .unwrap()
+            .starts_with("oxi-talib: line 13:"));
+    }
+
+    #[test]
+    fn test_write_detections() {
+        let candles: Vec<_> = (0..12)
+            .map(|i| {
+                SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0)
+                    .unwrap()
+                    .with_timestamp(i * 60_000)
+            })
+            .collect();
+        let detections = cdl().detect(Pattern::Doji, &candles).unwrap();
+
+        let mut out = Vec::new();
+        write_detections(&mut out, OutputFormat::Csv, &detections[..1]).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with("index,timestamp,pattern,quality\n10,600000,Doji,"));
+
+        let mut out = Vec::new();
+        write_detections(&mut out, OutputFormat::Table, &detections[..1]).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        assert_eq!(
+            table.lines().nth(1).unwrap().split_whitespace().nth(2),
+            Some("Doji")
+        );
+
+        let json = detection_json(&detections[0]);
+        assert_eq!(json["pattern"], "Doji");
+        assert_eq!(json["timestamp"], 600_000);
+    }
+}
@@ -0,0 +1,546 @@
+use crate::Error::InvalidInput;
+use crate::{Bias, Candle, Detection, Error, Pattern, SimpleCandle};
+
+/// The side of a position taken on a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Profits from rising prices.
+    Long,
+    /// Profits from falling prices.
+    Short,
+}
+
+impl Direction {
+    /// Returns the directions a pattern with the given bias is traded in.
+    ///
+    /// Bullish patterns are traded long and bearish ones short. Neutral patterns imply
+    /// no direction, so both are evaluated.
+    pub fn of(bias: Bias) -> &'static [Direction] {
+        match bias {
+            Bias::Bullish => &[Direction::Long],
+            Bias::Bearish => &[Direction::Short],
+            Bias::Neutral => &[Direction::Long, Direction::Short],
+        }
+    }
+
+    /// Returns the relative gain of a position opened at `entry` and valued at `price`.
+    pub(crate) fn gain(&self, entry: f64, price: f64) -> f64 {
+        match self {
+            Direction::Long => price / entry - 1.0,
+            Direction::Short => 1.0 - price / entry,
+        }
+    }
+}
+
+/// Statistics of the price move over a fixed number of candles after a pattern.
+///
+/// Returns and excursions are fractions of the entry price, the close of the candle
+/// the pattern was reported at, and are positive when the move is in the
+/// [`Direction`] of the position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizonStats {
+    /// The number of candles after the pattern.
+    pub horizon: usize,
+    /// The number of detections followed by at least `horizon` candles.
+    pub samples: usize,
+    /// The share of samples with a positive return, from 0 to 1.
+    pub hit_rate: f64,
+    /// The mean return at the close of the last candle of the horizon.
+    pub average_return: f64,
+    /// The median return at the close of the last candle of the horizon.
+    pub median_return: f64,
+    /// The mean of the largest moves against the position within the horizon.
+    pub average_adverse_excursion: f64,
+    /// The largest move against the position within the horizon over all samples.
+    pub max_adverse_excursion: f64,
+    /// The mean of the largest moves in favour of the position within the horizon.
+    pub average_favorable_excursion: f64,
+    /// The largest move in favour of the position within the horizon over all samples.
+    pub max_favorable_excursion: f64,
+}
+
+/// The historical performance of a pattern traded in one direction.
+///
+/// Returned from [`performance()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternPerformance {
+    /// The pattern evaluated.
+    pub pattern: Pattern,
+    /// The side of the position taken on the pattern.
+    pub direction: Direction,
+    /// The number of detections of the pattern.
+    pub detections: usize,
+    /// The statistics for each horizon, in the order the horizons were given.
+    pub horizons: Vec<HorizonStats>,
+}
+
+/// Measures how the price moved after each detected pattern.
+///
+/// The `detections` are the hits returned from [`crate::Cdl::detect`] or
+/// [`crate::Cdl::detect_all`] for the same `candles`. For every pattern and each
+/// [`Direction::of`] its bias, forward returns are calculated from the close of the
+/// candle a detection is reported at to the close `horizon` candles later, and the
+/// adverse and favourable excursions from the lows and highs in between. Detections
+/// too close to the end of the data for a horizon are counted in
+/// [`PatternPerformance::detections`] but not in [`HorizonStats::samples`]; the
+/// averages of a horizon without samples are `NaN`.
+///
+/// The result is ordered by [`Pattern::all()`] and then by direction.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if a horizon is 0 or a detection points past the
+/// end of `candles`, and [`Error::InvalidCandle`] if a candle is invalid.
+pub fn performance<C: Candle>(
+    candles: &[C],
+    detections: &[Detection],
+    horizons: &[usize],
+) -> Result<Vec<PatternPerformance>, Error> {
+    let candles = prepare(candles, detections, horizons)?;
+
+    let mut result = Vec::new();
+    for (pattern, indices) in group(detections) {
+        for &direction in Direction::of(pattern.info().bias) {
+            result.push(PatternPerformance {
+                pattern,
+                direction,
+                detections: indices.len(),
+                horizons: horizons
+                    .iter()
+                    .map(|&horizon| HorizonStats::measure(&candles, &indices, direction, horizon))
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+impl HorizonStats {
+    fn measure(
+        candles: &[SimpleCandle],
+        indices: &[usize],
+        direction: Direction,
+        horizon: usize,
+    ) -> Self {
+        let mut returns = Vec::new();
+        let mut adverse = Vec::new();
+        let mut favorable = Vec::new();
+
+        for &index in indices {
+            let window = index
+                .checked_add(horizon)
+                .and_then(|end| candles.get(index + 1..=end));
+            let Some(window) = window else {
+                continue;
+            };
+            let entry = candles[index].close();
+
+            returns.push(direction.gain(entry, window[horizon - 1].close()));
+            let (worst, best) = window.iter().fold((0.0f64, 0.0f64), |(worst, best), c| {
+                let low = direction.gain(entry, c.low());
+                let high = direction.gain(entry, c.high());
+                (worst.min(low).min(high), best.max(low).max(high))
+            });
+            adverse.push(-worst);
+            favorable.push(best);
+        }
+
+        let samples = returns.len();
+        let hits = returns.iter().filter(|&&value| value > 0.0).count();
+
+        Self {
+            horizon,
+            samples,
+            hit_rate: hits as f64 / samples as f64,
+            average_return: mean(&returns),
+            median_return: median(&mut returns),
+            average_adverse_excursion: mean(&adverse),
+            max_adverse_excursion: adverse.iter().copied().fold(f64::NAN, f64::max),
+            average_favorable_excursion: mean(&favorable),
+            max_favorable_excursion: favorable.iter().copied().fold(f64::NAN, f64::max),
+        }
+    }
+}
+
+/// The parameters of a [`significance()`] test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignificanceTest {
+    /// The number of candles after the pattern the return is measured over.
+    pub horizon: usize,
+    /// The number of random resamples.
+    pub iterations: usize,
+    /// The confidence level of the interval, e.g. 0.95.
+    pub confidence: f64,
+    /// The seed of the random number generator, so that a test can be repeated.
+    pub seed: u64,
+}
+
+impl Default for SignificanceTest {
+    /// A 10-candle horizon, 10 000 iterations and a 95% confidence level.
+    fn default() -> Self {
+        Self {
+            horizon: 10,
+            iterations: 10_000,
+            confidence: 0.95,
+            seed: 0,
+        }
+    }
+}
+
+/// The result of comparing the returns after a pattern with random entries.
+///
+/// Returned from [`significance()`]. Returns are fractions of the entry price and
+/// positive when the move is in the [`Direction`] of the position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternSignificance {
+    /// The pattern tested.
+    pub pattern: Pattern,
+    /// The side of the position taken on the pattern.
+    pub direction: Direction,
+    /// The number of detections followed by at least [`SignificanceTest::horizon`]
+    /// candles.
+    pub samples: usize,
+    /// The mean return after the pattern.
+    pub average_return: f64,
+    /// The mean return after every candle of the series.
+    pub baseline_return: f64,
+    /// The probability that random entries do at least as well as the pattern on
+    /// average.
+    pub p_value: f64,
+    /// The bootstrap confidence interval of the mean return after the pattern, as
+    /// the lower and the upper bound.
+    pub confidence_interval: (f64, f64),
+}
+
+/// Tests whether the returns after each pattern beat entering at random.
+///
+/// The baseline consists of the returns over [`SignificanceTest::horizon`] candles
+/// after every candle of the series. For every pattern and each [`Direction::of`] its
+/// bias, the mean return of its samples is compared with the means of
+/// [`SignificanceTest::iterations`] random entries, drawn from the baseline as many as
+/// there are samples. The p-value is the share of random draws with a mean at least as
+/// high, and the confidence interval is the percentile interval of the means of as
+/// many bootstrap resamples of the pattern's returns. The test is one-sided: a small
+/// p-value means the pattern did better than chance.
+///
+/// The detections are the hits returned from [`crate::Cdl::detect`] or
+/// [`crate::Cdl::detect_all`] for the same `candles`. The values of a pattern without
+/// samples are `NaN`. The result is ordered like that of [`performance()`] and is the
+/// same for the same [`SignificanceTest::seed`]. Every pattern and direction draws
+/// from its own random sequence, so its result does not depend on the other patterns
+/// detected.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if the horizon or the number of iterations is 0,
+/// the confidence is not between 0 and 1, or a detection points past the end of
+/// `candles`, and [`Error::InvalidCandle`] if a candle is invalid.
+pub fn significance<C: Candle>(
+    candles: &[C],
+    detections: &[Detection],
+    test: SignificanceTest,
+) -> Result<Vec<PatternSignificance>, Error> {
+    if test.iterations == 0 {
+        return Err(InvalidInput("iterations must be at least 1".into()));
+    }
+    if !(test.confidence > 0.0 && test.confidence < 1.0) {
+        return Err(InvalidInput(format!(
+            "confidence {} is not between 0 and 1",
+            test.confidence
+        )));
+    }
+    let candles = prepare(candles, detections, &[test.horizon])?;
+
+    let mut result = Vec::new();
+    for (pattern, indices) in group(detections) {
+        for &direction in Direction::of(pattern.info().bias) {
+            let mut random = SplitMix64::for_stream(test.seed, pattern, direction);
+            let forward = |index: usize| {
+                index
+                    .checked_add(test.horizon)
+                    .and_then(|exit| candles.get(exit))
+                    .map(|exit| direction.gain(candles[index].close(), exit.close()))
+            };
+            let baseline: Vec<f64> = (0..candles.len()).filter_map(forward).collect();
+            let returns: Vec<f64> = indices.iter().filter_map(|&index| forward(index)).collect();
+
+            let average = mean(&returns);
+            let (mut p_value, mut interval) = (f64::NAN, (f64::NAN, f64::NAN));
+            if !returns.is_empty() {
+                let random_means = random.resample_means(&baseline, returns.len(), test.iterations);
+                let beating = random_means.iter().filter(|&&m| m >= average).count();
+                p_value = (beating + 1) as f64 / (test.iterations + 1) as f64;
+
+                let mut means = random.resample_means(&returns, returns.len(), test.iterations);
+                means.sort_by(f64::total_cmp);
+                let tail = (1.0 - test.confidence) / 2.0;
+                let last = (test.iterations - 1) as f64;
+                interval = (
+                    means[(tail * last).floor() as usize],
+                    means[((1.0 - tail) * last).ceil() as usize],
+                );
+            }
+
+            result.push(PatternSignificance {
+                pattern,
+                direction,
+                samples: returns.len(),
+                average_return: average,
+                baseline_return: mean(&baseline),
+                p_value,
+                confidence_interval: interval,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// A small, fast pseudo-random number generator with a reproducible sequence.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Returns the generator of the random sequence of a pattern traded in a
+    /// direction, derived from `seed`.
+    fn for_stream(seed: u64, pattern: Pattern, direction: Direction) -> Self {
+        let position = Pattern::all()
+            .iter()
+            .position(|&other| other == pattern)
+            .expect("every pattern is listed in Pattern::all()");
+        let stream = 2 * position as u64 + (direction == Direction::Short) as u64;
+
+        let mut mixer = SplitMix64(seed ^ stream.wrapping_mul(0xD1B5_4A32_D192_ED03));
+        SplitMix64(mixer.next())
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns the means of `iterations` samples of `size` values drawn from `values`
+    /// with replacement.
+    fn resample_means(&mut self, values: &[f64], size: usize, iterations: usize) -> Vec<f64> {
+        if values.is_empty() {
+            return vec![f64::NAN; iterations];
+        }
+
+        (0..iterations)
+            .map(|_| {
+                let sum: f64 = (0..size)
+                    .map(|_| values[(self.next() % values.len() as u64) as usize])
+                    .sum();
+                sum / size as f64
+            })
+            .collect()
+    }
+}
+
+/// Validates the arguments of an analysis and converts the candles.
+fn prepare<C: Candle>(
+    candles: &[C],
+    detections: &[Detection],
+    horizons: &[usize],
+) -> Result<Vec<SimpleCandle>, Error> {
+    if horizons.contains(&0) {
+        return Err(InvalidInput("horizons must be at least 1 candle".into()));
+    }
+    if let Some(detection) = detections.iter().find(|d| d.index >= candles.len()) {
+        return Err(InvalidInput(format!(
+            "detection at candle #{} is past the end of {} candles",
+            detection.index,
+            candles.len()
+        )));
+    }
+
+    candles
+        .iter()
+        .map(|candle| SimpleCandle::try_from_candle(candle.clone()))
+        .collect()
+}
+
+/// Returns the indices of the detections of every pattern found, ordered by
+/// [`Pattern::all()`].
+fn group(detections: &[Detection]) -> Vec<(Pattern, Vec<usize>)> {
+    Pattern::all()
+        .iter()
+        .map(|&pattern| {
+            let indices: Vec<usize> = detections
+                .iter()
+                .filter(|detection| detection.pattern == pattern)
+                .map(|detection| detection.index)
+                .collect();
+            (pattern, indices)
+        })
+        .filter(|(_, indices)| !indices.is_empty())
+        .collect()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// `usize::is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pattern::*;
+
+    fn candle(close: f64, high: f64, low: f64) -> SimpleCandle {
+        SimpleCandle::try_new(close, close, high, low).unwrap()
+    }
+
+    #[test]
+    fn test_performance() {
+        let candles = vec![
+            candle(100.0, 100.0, 100.0),
+            candle(104.0, 106.0, 98.0),
+            candle(110.0, 112.0, 103.0),
+            candle(100.0, 101.0, 99.0),
+            candle(95.0, 101.0, 90.0),
+        ];
+        let detections = [
+            Detection::ending_at(Hammer, 0, 1),
+            Detection::ending_at(Hammer, 2, 1),
+            Detection::ending_at(HangingMan, 2, 1),
+            Detection::ending_at(Doji, 4, 1),
+        ];
+
+        let stats = performance(&candles, &detections, &[1, 2]).unwrap();
+
+        let found: Vec<_> = stats.iter().map(|s| (s.pattern, s.direction)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Hammer, Direction::Long),
+                (HangingMan, Direction::Short),
+                (Doji, Direction::Long),
+                (Doji, Direction::Short),
+            ]
+        );
+
+        let hammer = &stats[0];
+        assert_eq!(hammer.detections, 2);
+        let one = hammer.horizons[0];
+        assert_eq!(one.samples, 2);
+        assert_eq!(one.hit_rate, 0.5);
+        assert!((one.median_return - (0.04 + (100.0 / 110.0 - 1.0)) / 2.0).abs() < 1e-12);
+        let two = hammer.horizons[1];
+        assert_eq!(two.samples, 2);
+        assert!((two.average_return - (0.1 + (95.0 / 110.0 - 1.0)) / 2.0).abs() < 1e-12);
+        assert!((two.max_favorable_excursion - 0.12).abs() < 1e-12);
+        assert!((two.max_adverse_excursion - (1.0 - 90.0 / 110.0)).abs() < 1e-12);
+
+        let hanging_man = stats[1].horizons[1];
+        assert_eq!(hanging_man.hit_rate, 1.0);
+        assert!((hanging_man.average_return - (1.0 - 95.0 / 110.0)).abs() < 1e-12);
+
+        let doji = stats[2].horizons[0];
+        assert_eq!(doji.samples, 0);
+        assert!(doji.average_return.is_nan());
+
+        let stats = performance(&candles, &detections, &[usize::MAX]).unwrap();
+        assert_eq!(stats[0].horizons[0].samples, 0);
+    }
+
+    #[test]
+    fn test_performance_errors() {
+        let candles = vec![candle(100.0, 100.0, 100.0)];
+
+        assert!(performance(&candles, &[], &[0]).is_err());
+        assert!(performance(&candles, &[Detection::ending_at(Doji, 1, 1)], &[1]).is_err());
+        assert!(performance(&candles, &[], &[1]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_significance() {
+        // The close rises by 10% after every fourth candle and is flat or falls
+        // otherwise.
+        let candles: Vec<_> = (0..80)
+            .map(|i| match i % 4 {
+                2 => candle(110.0, 110.0, 110.0),
+                _ => candle(100.0, 100.0, 100.0),
+            })
+            .collect();
+        let before_rise: Vec<_> = (0..19)
+            .map(|i| Detection::ending_at(Hammer, 4 * i + 1, 1))
+            .collect();
+        let everywhere: Vec<_> = (0..79).map(|i| Detection::ending_at(Doji, i, 1)).collect();
+        let test = SignificanceTest {
+            horizon: 1,
+            iterations: 999,
+            ..Default::default()
+        };
+
+        let hammer = significance(&candles, &before_rise, test).unwrap()[0];
+        assert_eq!(hammer.samples, 19);
+        assert!((hammer.average_return - 0.1).abs() < 1e-12);
+        assert!(hammer.baseline_return < 0.01);
+        assert!(hammer.p_value < 0.01);
+        assert!((hammer.confidence_interval.0 - 0.1).abs() < 1e-12);
+        assert!((hammer.confidence_interval.1 - 0.1).abs() < 1e-12);
+
+        let doji = significance(&candles, &everywhere, test).unwrap();
+        assert_eq!(doji.len(), 2);
+        for result in &doji {
+            assert!(result.p_value > 0.1);
+            let (lower, upper) = result.confidence_interval;
+            assert!(lower <= result.average_return && result.average_return <= upper);
+        }
+        assert_eq!(doji, significance(&candles, &everywhere, test).unwrap());
+
+        // The results of a pattern do not depend on the other patterns detected.
+        let both: Vec<_> = before_rise.iter().chain(&everywhere).copied().collect();
+        let results = significance(&candles, &both, test).unwrap();
+        assert_eq!(results[0], hammer);
+        assert_eq!(results[1..], doji);
+
+        let far = SignificanceTest {
+            horizon: usize::MAX,
+            ..test
+        };
+        assert_eq!(
+            significance(&candles, &before_rise, far).unwrap()[0].samples,
+            0
+        );
+    }
+
+    #[test]
+    fn test_significance_errors() {
+        let candles = vec![candle(100.0, 100.0, 100.0)];
+        let test = SignificanceTest::default();
+
+        for invalid in [
+            SignificanceTest { horizon: 0, ..test },
+            SignificanceTest {
+                iterations: 0,
+                ..test
+            },
+            SignificanceTest {
+                confidence: 1.0,
+                ..test
+            },
+        ] {
+            assert!(significance(&candles, &[], invalid).is_err());
+        }
+    }
+}
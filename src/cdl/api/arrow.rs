@@ -0,0 +1,245 @@
+use crate::Error::{InvalidCandle, InvalidInput};
+use crate::{Candle, Cdl, Error, Pattern, SimpleCandle, Timestamp};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Int64Type, TimestampMillisecondType};
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch, UInt8Array};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use std::sync::Arc;
+
+/// The names of the columns of a [`RecordBatch`] read by [`candles_from_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrowColumns {
+    /// The `Float64` column of the opening price. Default is `"open"`.
+    pub open: String,
+    /// The `Float64` column of the highest price. Default is `"high"`.
+    pub high: String,
+    /// The `Float64` column of the lowest price. Default is `"low"`.
+    pub low: String,
+    /// The `Float64` column of the closing price. Default is `"close"`.
+    pub close: String,
+    /// The `Int64` or millisecond `Timestamp` column of the opening time, if any.
+    /// Default is `None`.
+    pub timestamp: Option<String>,
+    /// The `Float64` column of the traded volume, if any. Default is `None`.
+    pub volume: Option<String>,
+}
+
+impl Default for ArrowColumns {
+    fn default() -> Self {
+        Self {
+            open: "open".into(),
+            high: "high".into(),
+            low: "low".into(),
+            close: "close".into(),
+            timestamp: None,
+            volume: None,
+        }
+    }
+}
+
+/// Converts `Float64` price columns into candles.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if the columns differ in length and
+/// [`Error::InvalidCandle`] naming the row if a price is null or the prices do not
+/// form a valid candle.
+pub fn candles_from_arrays(
+    open: &Float64Array,
+    high: &Float64Array,
+    low: &Float64Array,
+    close: &Float64Array,
+) -> Result<Vec<SimpleCandle>, Error> {
+    let len = open.len();
+    if [high.len(), low.len(), close.len()]
+        .iter()
+        .any(|&l| l != len)
+    {
+        return Err(InvalidInput("price columns differ in length".into()));
+    }
+
+    (0..len)
+        .map(|row| {
+            let price = |column: &Float64Array| {
+                if column.is_null(row) {
+                    Err(InvalidCandle(format!("row {row}: a price is null")))
+                } else {
+                    Ok(column.value(row))
+                }
+            };
+
+            SimpleCandle::try_new(price(open)?, price(close)?, price(high)?, price(low)?)
+                .map_err(|e| InvalidCandle(format!("row {row}: {e}")))
+        })
+        .collect()
+}
+
+/// Converts the columns of a [`RecordBatch`] into candles.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidInput`] if a column is missing or has an unsupported type,
+/// and an [`Error`] under the same conditions as [`candles_from_arrays`] otherwise.
+pub fn candles_from_batch(
+    batch: &RecordBatch,
+    columns: &ArrowColumns,
+) -> Result<Vec<SimpleCandle>, Error> {
+    let mut candles = candles_from_arrays(
+        float_column(batch, &columns.open)?,
+        float_column(batch, &columns.high)?,
+        float_column(batch, &columns.low)?,
+        float_column(batch, &columns.close)?,
+    )?;
+
+    if let Some(name) = &columns.timestamp {
+        let timestamps = timestamp_column(batch, name)?;
+        for (row, candle) in candles.iter_mut().enumerate() {
+            if let Some(timestamp) = timestamps[row] {
+                *candle = candle.clone().with_timestamp(timestamp);
+            }
+        }
+    }
+    if let Some(name) = &columns.volume {
+        let volumes = float_column(batch, name)?;
+        for (row, candle) in candles.iter_mut().enumerate() {
+            if volumes.is_valid(row) {
+                *candle = candle
+                    .clone()
+                    .try_with_volume(volumes.value(row))
+                    .map_err(|e| InvalidCandle(format!("row {row}: {e}")))?;
+            }
+        }
+    }
+
+    Ok(candles)
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef, Error> {
+    batch
+        .column_by_name(name)
+        .ok_or_else(|| InvalidInput(format!("column \"{name}\" is not found")))
+}
+
+fn float_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a Float64Array, Error> {
+    column(batch, name)?
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or_else(|| InvalidInput(format!("column \"{name}\" is not Float64")))
+}
+
+fn timestamp_column(batch: &RecordBatch, name: &str) -> Result<Vec<Option<Timestamp>>, Error> {
+    let column = column(batch, name)?;
+
+    match column.data_type() {
+        DataType::Int64 => Ok(column.as_primitive::<Int64Type>().iter().collect()),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => Ok(column
+            .as_primitive::<TimestampMillisecondType>()
+            .iter()
+            .collect()),
+        other => Err(InvalidInput(format!(
+            "column \"{name}\" has unsupported type {other}"
+        ))),
+    }
+}
+
+impl Cdl {
+    /// Scans a slice of candles for several patterns and returns the results as a
+    /// [`RecordBatch`].
+    ///
+    /// The batch has one nullable `UInt8` column per pattern, named after its variant
+    /// (e.g. `"BullishEngulfing"`), with one row per candle holding the values of
+    /// [`Cdl::scores()`]: the quality of the signal, `0` if the pattern was not found,
+    /// and null for warm-up candles. Available with the `arrow` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::scores()`].
+    pub fn pattern_batch<C: Candle>(
+        &self,
+        patterns: &[Pattern],
+        candles: &[C],
+    ) -> Result<RecordBatch, Error> {
+        let mut fields = Vec::with_capacity(patterns.len());
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(patterns.len());
+        for &pattern in patterns {
+            fields.push(Field::new(format!("{pattern:?}"), DataType::UInt8, true));
+            arrays.push(Arc::new(UInt8Array::from(self.scores(pattern, candles)?)));
+        }
+
+        let schema = Arc::new(Schema::new(fields));
+        let options = arrow_array::RecordBatchOptions::new().with_row_count(Some(candles.len()));
+
+        RecordBatch::try_new_with_options(schema, arrays, &options)
+            .map_err(|e| InvalidInput(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cdl;
+    use crate::Pattern::*;
+    use arrow_array::Int64Array;
+
+    fn batch() -> RecordBatch {
+        let len = 15;
+        let column = |price: f64| -> ArrayRef { Arc::new(Float64Array::from(vec![price; len])) };
+        let timestamps: ArrayRef = Arc::new(Int64Array::from_iter_values(
+            (0..len as i64).map(|i| i * 60_000),
+        ));
+
+        RecordBatch::try_from_iter([
+            ("ts", timestamps),
+            ("open", column(10.0)),
+            ("high", column(11.0)),
+            ("low", column(9.0)),
+            ("close", column(10.0)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_candles_from_batch() {
+        let columns = ArrowColumns {
+            timestamp: Some("ts".into()),
+            ..Default::default()
+        };
+
+        let candles = candles_from_batch(&batch(), &columns).unwrap();
+
+        assert_eq!(candles.len(), 15);
+        assert_eq!(candles[2].timestamp(), Some(120_000));
+        assert_eq!(candles[2].high(), 11.0);
+
+        let columns = ArrowColumns {
+            volume: Some("volume".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            candles_from_batch(&batch(), &columns),
+            Err(InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_pattern_batch() {
+        let candles = candles_from_batch(&batch(), &ArrowColumns::default()).unwrap();
+
+        let result = cdl().pattern_batch(&[Doji, Hammer], &candles).unwrap();
+
+        assert_eq!(result.num_rows(), 15);
+        assert_eq!(result.schema().field(1).name(), "Hammer");
+
+        let doji = result
+            .column(0)
+            .as_primitive::<arrow_array::types::UInt8Type>();
+        assert!(doji.is_null(0));
+        assert!(doji.is_valid(14));
+        assert!(doji.value(14) > 0);
+
+        let hammer = result
+            .column(1)
+            .as_primitive::<arrow_array::types::UInt8Type>();
+        assert_eq!(hammer.value(14), 0);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::{Candle, Cdl, Detection, Direction, Error, Pattern, SimpleCandle};
+
+/// The conditions closing a trade of a [`Backtest`].
+///
+/// The stop and the target are derived from the candles forming the pattern that
+/// opened the trade. They are checked against the low and the high of every candle
+/// after the entry, the stop first, so a candle reaching both closes the trade at the
+/// stop. A trade still open at the end of the data is closed at the last close.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Exit {
+    /// Close the trade at the close of the candle this many candles after the entry.
+    pub bars: Option<usize>,
+    /// Close the trade when the price reaches or falls below the low of the pattern
+    /// for a long trade, or reaches or rises above its high for a short one.
+    pub stop: bool,
+    /// Close the trade when the price moves in favour of the position by this
+    /// multiple of the height of the pattern, i.e. its highest high minus its lowest
+    /// low.
+    pub target: Option<f64>,
+}
+
+/// The reason a [`Trade`] was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// [`Exit::bars`] candles passed.
+    Bars,
+    /// The [`Exit::stop`] was hit.
+    Stop,
+    /// The [`Exit::target`] was hit.
+    Target,
+    /// The data ended.
+    EndOfData,
+}
+
+/// A trade opened on a pattern and closed by an [`Exit`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trade {
+    /// The pattern that opened the trade.
+    pub pattern: Pattern,
+    /// The side of the position.
+    pub direction: Direction,
+    /// The index of the candle at whose close the trade was opened.
+    pub entry_index: usize,
+    /// The close of the entry candle.
+    pub entry_price: f64,
+    /// The index of the candle the trade was closed at.
+    pub exit_index: usize,
+    /// The price the trade was closed at.
+    pub exit_price: f64,
+    /// Why the trade was closed.
+    pub exit_reason: ExitReason,
+    /// The return of the trade as a fraction of the entry price.
+    pub gain: f64,
+}
+
+/// The outcome of [`Backtest::run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestResult {
+    /// The trades in the order they were opened.
+    pub trades: Vec<Trade>,
+    /// The equity at the close of every candle, starting from 1.0, with the open
+    /// trade valued at the close.
+    pub equity: Vec<f64>,
+}
+
+/// A deterministic backtest of trading on pattern detections.
+///
+/// A trade is opened at the close of the candle a detection of an entry pattern is
+/// reported at, and closed by the [`Exit`]. Only one trade is open at a time: entry
+/// signals are ignored while a trade is open, and when several entry patterns are
+/// found at the same candle, the one added first wins. The whole equity is invested in
+/// every trade, without costs.
+///
+/// The detections come from the given [`Cdl`], so stages such as a
+/// [`crate::Confirmation`] delay the entries.
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{cdl, Backtest, Direction, Exit, Pattern, SimpleCandle};
+///
+/// let candles = vec![SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0).unwrap(); 20];
+/// let exit = Exit {
+///     bars: Some(5),
+///     stop: true,
+///     ..Default::default()
+/// };
+///
+/// let result = Backtest::new(cdl(), exit)
+///     .with_entry(Pattern::BullishEngulfing, Direction::Long)
+///     .run(&candles)
+///     .unwrap();
+/// assert_eq!(result.equity.len(), candles.len());
+/// ```
+pub struct Backtest {
+    analyzer: Cdl,
+    entries: Vec<(Pattern, Direction)>,
+    exit: Exit,
+}
+
+impl Backtest {
+    /// Creates a backtest without entries, closing trades by `exit`.
+    pub fn new(analyzer: Cdl, exit: Exit) -> Self {
+        Self {
+            analyzer,
+            entries: Vec::new(),
+            exit,
+        }
+    }
+
+    /// Returns the backtest opening a trade in `direction` on every detection of
+    /// `pattern`.
+    pub fn with_entry(mut self, pattern: Pattern, direction: Direction) -> Self {
+        self.entries.push((pattern, direction));
+        self
+    }
+
+    /// Runs the backtest over the candles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`].
+    pub fn run<C: Candle>(&self, candles: &[C]) -> Result<BacktestResult, Error> {
+        let candles = candles
+            .iter()
+            .map(|candle| SimpleCandle::try_from_candle(candle.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut patterns: Vec<Pattern> = Vec::new();
+        for &(pattern, _) in &self.entries {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        let detections = self.analyzer.detect_all(&patterns, &candles)?;
+
+        Ok(self.simulate(&candles, &detections))
+    }
+
+    fn simulate(&self, candles: &[SimpleCandle], detections: &[Detection]) -> BacktestResult {
+        let mut trades = Vec::new();
+        let mut equity = Vec::with_capacity(candles.len());
+        let mut balance = 1.0;
+        let mut open: Option<Position> = None;
+        let mut detections = detections.iter().peekable();
+
+        for (index, candle) in candles.iter().enumerate() {
+            if let Some(position) = open {
+                if let Some((price, reason)) = position.exit(&self.exit, index, candle) {
+                    let trade = position.close(index, price, reason);
+                    balance *= 1.0 + trade.gain;
+                    trades.push(trade);
+                    open = None;
+                }
+            }
+
+            let mut found = Vec::new();
+            while let Some(detection) = detections.next_if(|d| d.index == index) {
+                found.push(detection);
+            }
+            if open.is_none() {
+                open = self.entries.iter().find_map(|&(pattern, direction)| {
+                    found
+                        .iter()
+                        .find(|detection| detection.pattern == pattern)
+                        .map(|detection| Position::open(detection, direction, candles))
+                });
+            }
+
+            equity.push(match open {
+                Some(position) => {
+                    balance * (1.0 + position.direction.gain(position.entry, candle.close()))
+                }
+                None => balance,
+            });
+        }
+
+        if let (Some(position), Some(last)) = (open, candles.last()) {
+            trades.push(position.close(candles.len() - 1, last.close(), ExitReason::EndOfData));
+        }
+
+        BacktestResult { trades, equity }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    pattern: Pattern,
+    direction: Direction,
+    index: usize,
+    entry: f64,
+    low: f64,
+    high: f64,
+}
+
+impl Position {
+    fn open(detection: &Detection, direction: Direction, candles: &[SimpleCandle]) -> Self {
+        let span = detection.signal.span();
+        let formed = &candles[span.start..=span.end()];
+
+        Self {
+            pattern: detection.pattern,
+            direction,
+            index: detection.index,
+            entry: candles[detection.index].close(),
+            low: formed.iter().map(|c| c.low()).fold(f64::INFINITY, f64::min),
+            high: formed
+                .iter()
+                .map(|c| c.high())
+                .fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    /// Returns the price and the reason the position is closed at the candle, if any.
+    fn exit(&self, exit: &Exit, index: usize, candle: &SimpleCandle) -> Option<(f64, ExitReason)> {
+        let height = self.high - self.low;
+
+        match self.direction {
+            Direction::Long => {
+                if exit.stop && candle.low() <= self.low {
+                    return Some((candle.open().min(self.low), ExitReason::Stop));
+                }
+                if let Some(target) = exit.target.map(|multiple| self.entry + multiple * height) {
+                    if candle.high() >= target {
+                        return Some((candle.open().max(target), ExitReason::Target));
+                    }
+                }
+            }
+            Direction::Short => {
+                if exit.stop && candle.high() >= self.high {
+                    return Some((candle.open().max(self.high), ExitReason::Stop));
+                }
+                if let Some(target) = exit.target.map(|multiple| self.entry - multiple * height) {
+                    if candle.low() <= target {
+                        return Some((candle.open().min(target), ExitReason::Target));
+                    }
+                }
+            }
+        }
+
+        if exit.bars.is_some_and(|bars| index - self.index >= bars) {
+            return Some((candle.close(), ExitReason::Bars));
+        }
+
+        None
+    }
+
+    fn close(&self, index: usize, price: f64, reason: ExitReason) -> Trade {
+        Trade {
+            pattern: self.pattern,
+            direction: self.direction,
+            entry_index: self.index,
+            entry_price: self.entry,
+            exit_index: index,
+            exit_price: price,
+            exit_reason: reason,
+            gain: self.direction.gain(self.entry, price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cdl;
+    use crate::Pattern::*;
+
+    fn candle(open: f64, close: f64, high: f64, low: f64) -> SimpleCandle {
+        SimpleCandle::try_new(open, close, high, low).unwrap()
+    }
+
+    #[test]
+    fn test_exits() {
+        let candles = vec![
+            candle(100.0, 98.0, 101.0, 97.0),
+            candle(98.0, 102.0, 103.0, 97.5),
+            candle(102.0, 104.0, 105.0, 101.0),
+            candle(104.0, 109.0, 110.0, 103.0),
+            candle(109.0, 108.0, 110.0, 107.0),
+            candle(108.0, 103.0, 108.0, 102.0),
+            candle(102.0, 103.0, 104.0, 101.5),
+        ];
+        let detections = [
+            Detection::ending_at(BullishEngulfing, 1, 2),
+            Detection::ending_at(BullishEngulfing, 2, 2),
+            Detection::ending_at(BearishEngulfing, 4, 2),
+        ];
+        let exit = Exit {
+            bars: Some(2),
+            stop: true,
+            target: Some(1.0),
+        };
+
+        let result = Backtest::new(cdl(), exit)
+            .with_entry(BullishEngulfing, Direction::Long)
+            .with_entry(BearishEngulfing, Direction::Short)
+            .simulate(&candles, &detections);
+
+        let trades: Vec<_> = result
+            .trades
+            .iter()
+            .map(|t| {
+                (
+                    t.pattern,
+                    t.entry_index,
+                    t.exit_index,
+                    t.exit_price,
+                    t.exit_reason,
+                )
+            })
+            .collect();
+        assert_eq!(
+            trades,
+            vec![
+                // Height 103 - 97 = 6, so the target is 102 + 6 = 108, reached at #3.
+                (BullishEngulfing, 1, 3, 108.0, ExitReason::Target),
+                // The target of 108 - 7 = 101 is not reached within 2 candles.
+                (BearishEngulfing, 4, 6, 103.0, ExitReason::Bars),
+            ]
+        );
+
+        assert_eq!(result.equity.len(), candles.len());
+        assert_eq!(result.equity[0], 1.0);
+        assert!((result.equity[2] - 104.0 / 102.0).abs() < 1e-12);
+        let first = 108.0 / 102.0;
+        assert!((result.equity[3] - first).abs() < 1e-12);
+        let last = first * (2.0 - 103.0 / 108.0);
+        assert!((result.equity[6] - last).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stop_and_end_of_data() {
+        let candles = vec![
+            candle(10.0, 11.0, 11.5, 9.5),
+            candle(11.0, 10.5, 11.0, 10.0),
+            candle(9.0, 9.5, 10.0, 8.0),
+            candle(9.5, 10.0, 10.5, 9.0),
+        ];
+        let exit = Exit {
+            stop: true,
+            ..Default::default()
+        };
+        let backtest = Backtest::new(cdl(), exit).with_entry(Hammer, Direction::Long);
+
+        let stopped = backtest.simulate(&candles, &[Detection::ending_at(Hammer, 0, 1)]);
+        // The candle opens below the stop, so the trade is closed at the open.
+        assert_eq!(stopped.trades[0].exit_index, 2);
+        assert_eq!(stopped.trades[0].exit_price, 9.0);
+        assert_eq!(stopped.trades[0].exit_reason, ExitReason::Stop);
+
+        let open = backtest.simulate(&candles, &[Detection::ending_at(Hammer, 2, 1)]);
+        assert_eq!(open.trades[0].exit_index, 3);
+        assert_eq!(open.trades[0].exit_reason, ExitReason::EndOfData);
+    }
+
+    #[test]
+    fn test_stop_touched() {
+        let candles = vec![candle(10.0, 11.0, 11.5, 9.5), candle(11.0, 10.5, 11.5, 9.5)];
+        let exit = Exit {
+            stop: true,
+            ..Default::default()
+        };
+        let detections = [Detection::ending_at(Hammer, 0, 1)];
+
+        // The second candle touches the low and the high of the first exactly.
+        let long = Backtest::new(cdl(), exit)
+            .with_entry(Hammer, Direction::Long)
+            .simulate(&candles, &detections);
+        assert_eq!(long.trades[0].exit_index, 1);
+        assert_eq!(long.trades[0].exit_price, 9.5);
+        assert_eq!(long.trades[0].exit_reason, ExitReason::Stop);
+
+        let short = Backtest::new(cdl(), exit)
+            .with_entry(Hammer, Direction::Short)
+            .simulate(&candles, &detections);
+        assert_eq!(short.trades[0].exit_index, 1);
+        assert_eq!(short.trades[0].exit_price, 11.5);
+        assert_eq!(short.trades[0].exit_reason, ExitReason::Stop);
+    }
+
+    #[test]
+    fn test_run_uses_detections() {
+        let candles: Vec<_> = (0..30)
+            .map(|i| {
+                let base = 100.0 + (i % 7) as f64;
+                candle(base, base + 0.02, base + 1.0, base - 1.0)
+            })
+            .collect();
+        let exit = Exit {
+            bars: Some(1),
+            ..Default::default()
+        };
+
+        let result = Backtest::new(cdl(), exit)
+            .with_entry(Doji, Direction::Long)
+            .run(&candles)
+            .unwrap();
+
+        let detections = cdl().detect(Doji, &candles).unwrap();
+        assert!(!result.trades.is_empty());
+        for trade in &result.trades {
+            assert!(detections.iter().any(|d| d.index == trade.entry_index));
+        }
+    }
+}
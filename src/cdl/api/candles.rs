@@ -1,13 +1,22 @@
 use crate::cdl::api::error::Error;
 use crate::cdl::api::error::Error::*;
+use crate::cdl::api::price::ToPrice;
+
+/// A point in time, in milliseconds since the Unix epoch (UTC).
+pub type Timestamp = i64;
 
 /// A trait for types that represent a candlestick.
 ///
 /// Implement this trait for your own data structures to make them compatible
 /// with the analysis functions.
+///
+/// Only the prices are required. The [`Candle::timestamp`] and [`Candle::volume`]
+/// methods are optional and return `None` by default.
 pub trait Candle: Clone {
     /// The numeric type for price values. Must be convertible to `f64`.
-    type Price: Into<f64> + Copy;
+    ///
+    /// See [`ToPrice`] for the supported types.
+    type Price: ToPrice + Copy;
 
     /// The opening price.
     fn open(&self) -> Self::Price;
@@ -17,15 +26,27 @@ pub trait Candle: Clone {
     fn high(&self) -> Self::Price;
     /// The lowest price.
     fn low(&self) -> Self::Price;
+
+    /// The opening time of the candle, if known.
+    fn timestamp(&self) -> Option<Timestamp> {
+        None
+    }
+
+    /// The traded volume, if known.
+    fn volume(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// A basic, validated implementation of the [`Candle`] trait.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SimpleCandle {
     open: f64,
     close: f64,
     high: f64,
     low: f64,
+    timestamp: Option<Timestamp>,
+    volume: Option<f64>,
 }
 
 impl SimpleCandle {
@@ -57,21 +78,67 @@ impl SimpleCandle {
             close,
             high,
             low,
+            timestamp: None,
+            volume: None,
+        })
+    }
+
+    /// Returns the candle with the given opening time.
+    pub fn with_timestamp(self, timestamp: Timestamp) -> Self {
+        Self {
+            timestamp: Some(timestamp),
+            ..self
+        }
+    }
+
+    /// Returns the candle with the given traded volume.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCandle`] if `volume` is negative or not a finite number.
+    pub fn try_with_volume(self, volume: f64) -> Result<Self, Error> {
+        if !volume.is_finite() || volume < 0.0 {
+            return Err(InvalidCandle(
+                "a volume must be a non-negative finite number".into(),
+            ));
+        }
+
+        Ok(Self {
+            volume: Some(volume),
+            ..self
         })
     }
 
     /// Converts a type implementing [`Candle`] into a `SimpleCandle`.
     ///
+    /// The timestamp and volume of the source candle are preserved.
+    ///
     /// # Errors
     ///
-    /// Returns [`Error::InvalidCandle`] if the source candle's prices are not valid.
+    /// Returns [`Error::InvalidCandle`] if the source candle's prices or volume are not valid,
+    /// or if the prices cannot be converted to `f64`.
     pub fn try_from_candle<C: Candle>(candle: C) -> Result<Self, Error> {
-        Self::try_new(
-            candle.open().into(),
-            candle.close().into(),
-            candle.high().into(),
-            candle.low().into(),
-        )
+        let mut simple = Self::try_new(
+            Self::price(candle.open())?,
+            Self::price(candle.close())?,
+            Self::price(candle.high())?,
+            Self::price(candle.low())?,
+        )?;
+
+        if let Some(timestamp) = candle.timestamp() {
+            simple = simple.with_timestamp(timestamp);
+        }
+        if let Some(volume) = candle.volume() {
+            simple = simple.try_with_volume(volume)?;
+        }
+
+        Ok(simple)
+    }
+
+    fn price<P: ToPrice>(price: P) -> Result<f64, Error> {
+        price
+            .to_f64()
+            .ok_or_else(|| InvalidCandle("a price cannot be converted to f64".into()))
     }
 }
 
@@ -93,4 +160,45 @@ impl Candle for SimpleCandle {
     fn low(&self) -> Self::Price {
         self.low
     }
+
+    fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+
+    fn volume(&self) -> Option<f64> {
+        self.volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_with_volume() {
+        let candle = SimpleCandle::try_new(100.0, 102.0, 103.0, 99.0).unwrap();
+
+        assert_eq!(candle.volume(), None);
+        assert_eq!(
+            candle.clone().try_with_volume(10.0).unwrap().volume(),
+            Some(10.0)
+        );
+        assert!(candle.clone().try_with_volume(-1.0).is_err());
+        assert!(candle.try_with_volume(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_try_from_candle_keeps_timestamp_and_volume() {
+        let source = SimpleCandle::try_new(100.0, 102.0, 103.0, 99.0)
+            .unwrap()
+            .with_timestamp(1_700_000_000_000)
+            .try_with_volume(1500.0)
+            .unwrap();
+
+        let candle = SimpleCandle::try_from_candle(source.clone()).unwrap();
+
+        assert_eq!(candle.timestamp(), Some(1_700_000_000_000));
+        assert_eq!(candle.volume(), Some(1500.0));
+        assert_eq!(candle, source);
+    }
 }
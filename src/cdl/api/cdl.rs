@@ -1,7 +1,13 @@
-use crate::cdl::engines::internal::CdlApiInternal;
+use crate::cdl::engines::internal::{CdlApiInternal, Filters};
+use crate::Error::InvalidCandle;
 use crate::Pattern;
 use crate::Signal;
-use crate::{Candle, Error, SimpleCandle};
+use crate::{
+    resample, Candle, Confirmation, Confluence, ConfluenceSignal, Detection, Error, Evaluation,
+    Explanation, GapCheck, SimpleCandle, Timeframe, TimeframeDetection, TrendFilter, VolumeFilter,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
 
 /// The main struct for candlestick pattern analysis.
 ///
@@ -17,13 +23,83 @@ use crate::{Candle, Error, SimpleCandle};
 /// spawning any other threads that use this crate.
 ///
 /// If not explicitly configured, balanced default settings built into this crate will be used.
+///
+/// # Thread Safety
+///
+/// `Cdl` is `Send` and `Sync`. The engine settings are applied once, when the engine
+/// is initialized by the first call to [`crate::cdl()`], and are only read afterwards,
+/// so any number of threads may scan through the same or different `Cdl` instances
+/// concurrently. [`Cdl::scan_universe()`] relies on this guarantee.
 pub struct Cdl {
     internal: &'static (dyn CdlApiInternal + Send + Sync),
+    confirmation: Option<Confirmation>,
+    gap_check: Option<GapCheck>,
+    filters: Filters,
 }
 
 impl Cdl {
     pub(crate) fn new(internal: &'static (dyn CdlApiInternal + Send + Sync)) -> Self {
-        Cdl { internal }
+        Cdl {
+            internal,
+            confirmation: None,
+            gap_check: None,
+            filters: internal.filters(),
+        }
+    }
+
+    /// Returns an analyzer that reports signals only after they are confirmed by the
+    /// following candles.
+    ///
+    /// The confirmation applies to [`Cdl::pattern()`], [`Cdl::detect()`] and
+    /// [`Cdl::detect_all()`]. See [`Confirmation`] for details.
+    pub fn with_confirmation(self, confirmation: Confirmation) -> Self {
+        Self {
+            confirmation: Some(confirmation),
+            ..self
+        }
+    }
+
+    /// Returns an analyzer that does not report patterns spanning a hole in the data.
+    ///
+    /// The check applies to [`Cdl::pattern()`], [`Cdl::detect()`] and
+    /// [`Cdl::detect_all()`], which then require timestamped candles.
+    /// [`Cdl::evaluate()`] reports such patterns as unavailable. See [`GapCheck`] for
+    /// details.
+    pub fn with_gap_check(self, gap_check: GapCheck) -> Self {
+        Self {
+            gap_check: Some(gap_check),
+            ..self
+        }
+    }
+
+    /// Returns an analyzer that checks the trend preceding reversal patterns with
+    /// `filter`, instead of the [`crate::Settings::trend_filter`].
+    ///
+    /// The filter applies to every method scanning for patterns and to
+    /// [`Cdl::explain()`]. See [`TrendFilter`] for details.
+    pub fn with_trend_filter(self, filter: TrendFilter) -> Self {
+        Self {
+            filters: Filters {
+                trend: Some(filter),
+                ..self.filters
+            },
+            ..self
+        }
+    }
+
+    /// Returns an analyzer that checks the volume of patterns with `filter`, instead of
+    /// the [`crate::Settings::volume_filter`].
+    ///
+    /// The filter applies to every method scanning for patterns and to
+    /// [`Cdl::explain()`]. See [`VolumeFilter`] for details.
+    pub fn with_volume_filter(self, filter: VolumeFilter) -> Self {
+        Self {
+            filters: Filters {
+                volume: Some(filter),
+                ..self.filters
+            },
+            ..self
+        }
     }
 
     /// Scans a slice of candles for a specific pattern.
@@ -46,13 +122,424 @@ impl Cdl {
         pattern: Pattern,
         candles: &[C],
     ) -> Result<Vec<Option<Signal>>, Error> {
-        let candles = candles
+        let candles = Self::simple_candles(candles)?;
+
+        let signals = self.signals(pattern, &candles)?;
+
+        Ok(signals)
+    }
+
+    /// Scans a slice of candles for a specific pattern and returns a dense score series.
+    ///
+    /// Returns a `Vec<Option<u8>>` of the same length as the input `candles` slice:
+    /// the [`crate::Quality`] value of the signal where the pattern was found, `0` where
+    /// it was not, and `None` for the leading warm-up candles the engine cannot
+    /// evaluate yet (and for candles that are [`Evaluation::Unavailable`]). This is
+    /// the representation used for columnar output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::evaluate()`].
+    pub fn scores<C: Candle>(
+        &self,
+        pattern: Pattern,
+        candles: &[C],
+    ) -> Result<Vec<Option<u8>>, Error> {
+        let candles = Self::simple_candles(candles)?;
+
+        let (signals, unevaluated) = self.evaluated_signals(pattern, &candles)?;
+
+        Ok(signals
+            .into_iter()
+            .zip(unevaluated)
+            .map(|(signal, unevaluated)| {
+                if unevaluated {
+                    None
+                } else {
+                    Some(signal.map_or(0, |signal| signal.quality.value()))
+                }
+            })
+            .collect())
+    }
+
+    /// Scans a slice of candles for a specific pattern and tells missing data apart
+    /// from the absence of the pattern.
+    ///
+    /// Returns a `Vec<Evaluation>` of the same length as the input `candles` slice. A
+    /// candle is [`Evaluation::Unavailable`] if a [`GapCheck`] is set and the pattern
+    /// ending at the candle would span a hole in the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`] and
+    /// [`GapCheck::find()`].
+    pub fn evaluate<C: Candle>(
+        &self,
+        pattern: Pattern,
+        candles: &[C],
+    ) -> Result<Vec<Evaluation>, Error> {
+        let candles = Self::simple_candles(candles)?;
+
+        let signals = self.signals(pattern, &candles)?;
+        let unavailable = self.unavailable(pattern, &candles)?;
+
+        Ok(signals
+            .into_iter()
+            .zip(unavailable)
+            .map(|(signal, unavailable)| match signal {
+                _ if unavailable => Evaluation::Unavailable,
+                Some(signal) => Evaluation::Detected(signal),
+                None => Evaluation::NotDetected,
+            })
+            .collect())
+    }
+
+    /// Scans a slice of candles for a specific pattern and returns only the hits.
+    ///
+    /// Unlike [`Cdl::pattern()`], the result is sparse: it contains one [`Detection`]
+    /// per candle at which the pattern was found, in ascending order of the index.
+    /// The timestamp of a detection is taken from [`Candle::timestamp`] when the
+    /// candle provides one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`].
+    pub fn detect<C: Candle>(
+        &self,
+        pattern: Pattern,
+        candles: &[C],
+    ) -> Result<Vec<Detection>, Error> {
+        self.detect_all(&[pattern], candles)
+    }
+
+    /// Scans a slice of candles for several patterns and returns only the hits.
+    ///
+    /// Detections are ordered by the candle index and, for the same candle,
+    /// by the order of `patterns`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`].
+    pub fn detect_all<C: Candle>(
+        &self,
+        patterns: &[Pattern],
+        candles: &[C],
+    ) -> Result<Vec<Detection>, Error> {
+        let candles = Self::simple_candles(candles)?;
+
+        let mut detections = Vec::new();
+        for &pattern in patterns {
+            let signals = self.signals(pattern, &candles)?;
+            detections.extend(Detection::collect(pattern, &candles, &signals));
+        }
+        detections.sort_by_key(|detection| detection.index);
+
+        Ok(detections)
+    }
+
+    /// Scans many instruments for several patterns.
+    ///
+    /// Takes a map of symbol to candles and returns the [`Cdl::detect_all()`] result of
+    /// every symbol, keyed by the symbol. An error in one series does not affect the
+    /// others. With the `rayon` feature, the symbols are scanned in parallel on the
+    /// global Rayon thread pool; see the thread safety section above.
+    pub fn scan_universe<K, V, C>(
+        &self,
+        universe: &HashMap<K, V>,
+        patterns: &[Pattern],
+    ) -> HashMap<K, Result<Vec<Detection>, Error>>
+    where
+        K: Eq + Hash + Clone + Send + Sync,
+        V: AsRef<[C]> + Sync,
+        C: Candle + Sync,
+    {
+        let scan = |(symbol, candles): (&K, &V)| {
+            (symbol.clone(), self.detect_all(patterns, candles.as_ref()))
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            universe.par_iter().map(scan).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            universe.iter().map(scan).collect()
+        }
+    }
+
+    /// Resamples the candles into several timeframes and scans each of them for
+    /// several patterns.
+    ///
+    /// Every detection is mapped back to the range of source candles forming the
+    /// resampled candle it was found at. Detections are ordered by the last source
+    /// candle, i.e. by the time they become known, then by the order of `timeframes`
+    /// and `patterns`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`crate::resample()`] and
+    /// [`Cdl::pattern()`].
+    pub fn detect_timeframes<C: Candle>(
+        &self,
+        patterns: &[Pattern],
+        candles: &[C],
+        timeframes: &[Timeframe],
+    ) -> Result<Vec<TimeframeDetection>, Error> {
+        let mut detections = Vec::new();
+        for &timeframe in timeframes {
+            let resampled = resample(candles, timeframe)?;
+
+            detections.extend(
+                self.detect_all(patterns, &resampled.candles)?
+                    .into_iter()
+                    .map(|detection| TimeframeDetection {
+                        timeframe,
+                        source: resampled.sources[detection.index].clone(),
+                        detection,
+                    }),
+            );
+        }
+        detections.sort_by_key(|detection| detection.source.end);
+
+        Ok(detections)
+    }
+
+    /// Scans several series of the same instrument for patterns appearing at the same
+    /// time on different timeframes.
+    ///
+    /// Each series is given with its [`Timeframe`], which defines the period covered by
+    /// each of its candles. The first series anchors the events: every candle of it at
+    /// which any of `patterns` is found produces a [`Confluence`] with the detections of
+    /// the other series whose candle periods overlap it. Only events in which at least
+    /// `min_timeframes` distinct timeframes participate are returned, in the order of
+    /// the anchor candles.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCandle`] if a candle has no timestamp,
+    /// [`Error::InvalidTimeframe`] if a timeframe is invalid, and an [`Error`] under the
+    /// same conditions as [`Cdl::pattern()`] otherwise.
+    pub fn confluence<C: Candle>(
+        &self,
+        patterns: &[Pattern],
+        series: &[(Timeframe, &[C])],
+        min_timeframes: usize,
+    ) -> Result<Vec<Confluence>, Error> {
+        let mut periods = Vec::with_capacity(series.len());
+        for &(timeframe, candles) in series {
+            timeframe.validate()?;
+            if let Some(index) = candles.iter().position(|c| c.timestamp().is_none()) {
+                return Err(InvalidCandle(format!("candle #{index} has no timestamp")));
+            }
+
+            let mut signals = Vec::new();
+            for detection in self.detect_all(patterns, candles)? {
+                let Some(start) = detection
+                    .timestamp
+                    .and_then(|timestamp| timeframe.period_start(timestamp))
+                else {
+                    continue;
+                };
+                let Some(end) = timeframe.period_end(start) else {
+                    continue;
+                };
+                signals.push((
+                    start,
+                    end,
+                    ConfluenceSignal {
+                        timeframe,
+                        detection,
+                    },
+                ));
+            }
+            periods.push(signals);
+        }
+
+        let Some((anchors, others)) = periods.split_first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut events = Vec::new();
+        for group in anchors.chunk_by(|a, b| a.2.detection.index == b.2.detection.index) {
+            let (start, end, _) = group[0];
+            let mut signals: Vec<_> = group.iter().map(|&(_, _, signal)| signal).collect();
+            for other in others {
+                signals.extend(
+                    other
+                        .iter()
+                        .filter(|&&(s, e, _)| s < end && e > start)
+                        .map(|&(_, _, signal)| signal),
+                );
+            }
+
+            let event = Confluence {
+                start,
+                end,
+                signals,
+            };
+            if event.timeframes().len() >= min_timeframes {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Explains why a pattern was or was not detected at a specific candle.
+    ///
+    /// Returns every rule of the pattern with the measured value, the threshold
+    /// derived from the [`crate::Settings`] and the rolling average of the preceding
+    /// candles, and whether the rule passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The [`Pattern`] to evaluate.
+    /// * `candles`: A slice of items that implement the [`Candle`] trait.
+    /// * `index`: The index of the candle to evaluate the pattern at (the last candle
+    ///   of the pattern).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidIndex`] if `index` is out of range, there are not enough
+    /// candles before it to form the pattern, or it lies in the warm-up the engine does
+    /// not evaluate (see [`Cdl::scores()`]), and an [`Error`] under the same conditions
+    /// as [`Cdl::pattern()`] otherwise.
+    pub fn explain<C: Candle>(
+        &self,
+        pattern: Pattern,
+        candles: &[C],
+        index: usize,
+    ) -> Result<Explanation, Error> {
+        let candles = Self::simple_candles(candles)?;
+
+        self.internal
+            .explain(pattern, &candles, index, &self.filters)
+    }
+
+    /// The number of candles a signal at the last candle depends on.
+    pub(crate) fn history(&self) -> usize {
+        self.internal.history(&self.filters)
+            + self
+                .confirmation
+                .map_or(0, |confirmation| confirmation.bars)
+    }
+
+    fn signals(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+    ) -> Result<Vec<Option<Signal>>, Error> {
+        Ok(self.scan(pattern, candles)?.0)
+    }
+
+    /// Returns the signals and, for each candle, whether the pattern could not be
+    /// evaluated there: within the warm-up or because it is [`Evaluation::Unavailable`].
+    pub(crate) fn evaluated_signals(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+    ) -> Result<(Vec<Option<Signal>>, Vec<bool>), Error> {
+        let (signals, warm_up) = self.scan(pattern, candles)?;
+        let mut unevaluated = self.unavailable(pattern, candles)?;
+        unevaluated[..warm_up.min(candles.len())].fill(true);
+
+        Ok((signals, unevaluated))
+    }
+
+    /// Returns the signals with the number of leading candles the engine does not
+    /// evaluate.
+    fn scan(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+    ) -> Result<(Vec<Option<Signal>>, usize), Error> {
+        let (mut signals, warm_up) = self.internal.pattern(pattern, candles, &self.filters)?;
+        for (signal, unavailable) in signals
+            .iter_mut()
+            .zip(self.spanning_gaps(pattern, candles)?)
+        {
+            if unavailable {
+                *signal = None;
+            }
+        }
+
+        let signals = match self.confirmation {
+            Some(confirmation) => confirmation.apply(pattern, candles, signals),
+            None => signals,
+        };
+
+        Ok((signals, warm_up))
+    }
+
+    /// Marks the candles at which a signal spanning a hole in the data is reported,
+    /// i.e. the confirming candles when a confirmation is used.
+    fn unavailable(&self, pattern: Pattern, candles: &[SimpleCandle]) -> Result<Vec<bool>, Error> {
+        let spanning_gaps = self.spanning_gaps(pattern, candles)?;
+        let offset = self
+            .confirmation
+            .map_or(0, |confirmation| confirmation.offset(pattern))
+            .min(candles.len());
+
+        let mut unavailable = vec![false; offset];
+        unavailable.extend_from_slice(&spanning_gaps[..candles.len() - offset]);
+
+        Ok(unavailable)
+    }
+
+    /// Marks the candles at which a pattern spanning a hole in the data is detected.
+    fn spanning_gaps(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+    ) -> Result<Vec<bool>, Error> {
+        Ok(match self.gap_check {
+            Some(gap_check) => {
+                GapCheck::unavailable(pattern, &gap_check.find(candles)?, candles.len())
+            }
+            None => vec![false; candles.len()],
+        })
+    }
+
+    fn simple_candles<C: Candle>(candles: &[C]) -> Result<Vec<SimpleCandle>, Error> {
+        candles
             .iter()
             .map(|candle| SimpleCandle::try_from_candle(candle.clone()))
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect()
+    }
+}
 
-        let signals = self.internal.pattern(pattern, &candles)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cdl;
 
-        Ok(signals)
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_scan_universe() {
+        assert_send_sync::<Cdl>();
+
+        let doji = SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0).unwrap();
+
+        let mut universe: HashMap<String, Vec<SimpleCandle>> = (0..50)
+            .map(|i| (format!("T{i}"), vec![doji.clone(); 12 + i]))
+            .collect();
+        universe.insert("EMPTY".into(), Vec::new());
+
+        let results = cdl().scan_universe(&universe, &[Pattern::Doji]);
+
+        assert_eq!(results.len(), 51);
+        assert!(results["EMPTY"].as_ref().unwrap().is_empty());
+        for i in 0..50 {
+            let detections = results[&format!("T{i}")].as_ref().unwrap();
+            assert_eq!(detections.len(), 2 + i);
+            assert_eq!(
+                detections,
+                &cdl()
+                    .detect(Pattern::Doji, &universe[&format!("T{i}")])
+                    .unwrap()
+            );
+        }
     }
 }
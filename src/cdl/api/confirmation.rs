@@ -0,0 +1,159 @@
+use crate::{Bias, Candle, Pattern, Signal, SimpleCandle};
+
+/// A rule that a candle following a pattern must satisfy to confirm it.
+///
+/// The rules are directional: for a bullish pattern the confirming candle must move
+/// up, for a bearish one it must move down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationRule {
+    /// The candle closes beyond the real body of the last pattern candle, e.g. above
+    /// the body of a `Hammer`.
+    CloseBeyondBody,
+    /// The candle closes beyond the extreme of the pattern, i.e. above the highest high
+    /// of a bullish pattern or below the lowest low of a bearish one.
+    CloseBeyondExtreme,
+    /// The candle has the color of the pattern's bias: white for a bullish pattern and
+    /// black for a bearish one.
+    Color,
+}
+
+/// An opt-in confirmation stage for [`crate::Cdl`].
+///
+/// Textbook practice waits for the candles following a reversal to confirm it. With a
+/// confirmation set via [`crate::Cdl::with_confirmation`], a signal is reported at the
+/// confirming candle, `bars` candles after the pattern, only if each of those candles
+/// satisfies the `rule`. The original detection index stays available from
+/// [`crate::Signal::detected_at`].
+///
+/// Signals of patterns with a [`crate::Bias::Neutral`] bias cannot be confirmed and are
+/// reported at the pattern unchanged. Signals too close to the end of the data to be
+/// confirmed are dropped. A `bars` value of 0 disables the confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmation {
+    /// The number of candles after the pattern that must satisfy the rule.
+    pub bars: usize,
+    /// The rule each confirming candle must satisfy.
+    pub rule: ConfirmationRule,
+}
+
+impl Confirmation {
+    /// Returns the number of candles the signals of `pattern` are moved forward by.
+    pub(crate) fn offset(&self, pattern: Pattern) -> usize {
+        match pattern.info().bias {
+            Bias::Neutral => 0,
+            _ => self.bars,
+        }
+    }
+
+    /// Moves every confirmed signal to its confirming candle and drops the others.
+    pub(crate) fn apply(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+        signals: Vec<Option<Signal>>,
+    ) -> Vec<Option<Signal>> {
+        let bias = pattern.info().bias;
+        if self.bars == 0 || bias == Bias::Neutral {
+            return signals;
+        }
+
+        let mut confirmed = vec![None; signals.len()];
+        for (index, signal) in signals.into_iter().enumerate() {
+            let Some(signal) = signal else {
+                continue;
+            };
+
+            let at = index + self.bars;
+            if at < candles.len() && self.confirms(bias, &candles[signal.span().start..=at], signal)
+            {
+                confirmed[at] = Some(signal.with_confirmed_at(at));
+            }
+        }
+
+        confirmed
+    }
+
+    /// Checks the candles following the pattern. `candles` starts with the pattern.
+    fn confirms(&self, bias: Bias, candles: &[SimpleCandle], signal: Signal) -> bool {
+        let (pattern, following) = candles.split_at(signal.span().len);
+        let last = &pattern[pattern.len() - 1];
+
+        following.iter().all(|candle| match (self.rule, bias) {
+            (ConfirmationRule::CloseBeyondBody, Bias::Bullish) => {
+                candle.close() > last.open().max(last.close())
+            }
+            (ConfirmationRule::CloseBeyondBody, _) => {
+                candle.close() < last.open().min(last.close())
+            }
+            (ConfirmationRule::CloseBeyondExtreme, Bias::Bullish) => {
+                candle.close() > pattern.iter().map(|c| c.high()).fold(f64::MIN, f64::max)
+            }
+            (ConfirmationRule::CloseBeyondExtreme, _) => {
+                candle.close() < pattern.iter().map(|c| c.low()).fold(f64::MAX, f64::min)
+            }
+            (ConfirmationRule::Color, Bias::Bullish) => candle.close() > candle.open(),
+            (ConfirmationRule::Color, _) => candle.close() < candle.open(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cdl, Pattern::*};
+
+    /// A declining series, a hammer at index 11 and the given candles after it.
+    fn hammer_followed_by(following: &[(f64, f64)]) -> Vec<SimpleCandle> {
+        let mut candles: Vec<_> = (0..11)
+            .map(|i| {
+                let open = 21.0 - i as f64;
+                SimpleCandle::try_new(open, open - 1.0, open + 0.2, open - 1.2).unwrap()
+            })
+            .collect();
+        candles.push(SimpleCandle::try_new(10.2, 10.0, 10.2, 7.0).unwrap());
+        candles.extend(following.iter().map(|&(open, close)| {
+            SimpleCandle::try_new(open, close, open.max(close), open.min(close)).unwrap()
+        }));
+        candles
+    }
+
+    #[test]
+    fn test_confirmed_signal_moves_to_confirming_bar() {
+        let candles = hammer_followed_by(&[(10.1, 10.6), (10.6, 11.0)]);
+        let analyzer = cdl().with_confirmation(Confirmation {
+            bars: 2,
+            rule: ConfirmationRule::CloseBeyondBody,
+        });
+
+        let signals = analyzer.pattern(Hammer, &candles).unwrap();
+        assert!(signals[11].is_none());
+
+        let signal = signals[13].expect("Hammer should be confirmed at candle #13");
+        assert_eq!(signal.detected_at(), 11);
+        assert_eq!(signal.confirmed_at(), Some(13));
+
+        let detections = analyzer.detect(Hammer, &candles).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].index, 13);
+    }
+
+    #[test]
+    fn test_unconfirmed_signal_is_dropped() {
+        let candles = hammer_followed_by(&[(10.1, 10.6), (10.6, 10.1)]);
+        let confirmation = |rule| Confirmation { bars: 2, rule };
+
+        for rule in [ConfirmationRule::CloseBeyondBody, ConfirmationRule::Color] {
+            let signals = cdl()
+                .with_confirmation(confirmation(rule))
+                .pattern(Hammer, &candles)
+                .unwrap();
+            assert!(signals.iter().all(Option::is_none), "{rule:?}");
+        }
+
+        let signals = cdl()
+            .with_confirmation(confirmation(ConfirmationRule::CloseBeyondExtreme))
+            .pattern(Hammer, &hammer_followed_by(&[(10.1, 10.6)]))
+            .unwrap();
+        assert!(signals.iter().all(Option::is_none));
+    }
+}
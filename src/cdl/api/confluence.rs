@@ -0,0 +1,84 @@
+use crate::{Detection, Timeframe, Timestamp};
+
+/// A pattern detected on one of the timeframes of a [`Confluence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfluenceSignal {
+    /// The timeframe of the series the pattern was found in.
+    pub timeframe: Timeframe,
+    /// The detection, indexed into the candles of that series.
+    pub detection: Detection,
+}
+
+/// Patterns appearing at the same time on several timeframes of an instrument.
+///
+/// Returned from [`crate::Cdl::confluence`]. An event is anchored at a candle of the
+/// first series passed, where at least one of the patterns was found. The candles of
+/// the other series are aligned with it when their periods overlap the period of the
+/// anchor candle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confluence {
+    /// The start of the period of the anchor candle.
+    pub start: Timestamp,
+    /// The end of the period of the anchor candle, exclusive.
+    pub end: Timestamp,
+    /// The detections aligned with the anchor candle, ordered by the series and then
+    /// by the candle index. The detections of the anchor candle come first.
+    pub signals: Vec<ConfluenceSignal>,
+}
+
+impl Confluence {
+    /// Returns the distinct timeframes participating in the event, in the order of
+    /// the series.
+    pub fn timeframes(&self) -> Vec<Timeframe> {
+        let mut timeframes: Vec<Timeframe> = Vec::new();
+        for signal in &self.signals {
+            if !timeframes.contains(&signal.timeframe) {
+                timeframes.push(signal.timeframe);
+            }
+        }
+
+        timeframes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cdl, resample, Pattern, SimpleCandle, Timeframe};
+
+    #[test]
+    fn test_confluence_aligns_overlapping_candles() {
+        let minutes: Vec<_> = (0..60)
+            .map(|i| {
+                SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0)
+                    .unwrap()
+                    .with_timestamp(i * 60_000)
+            })
+            .collect();
+        let three_minutes = resample(&minutes, Timeframe::minutes(3)).unwrap().candles;
+        let series = [
+            (Timeframe::minutes(3), three_minutes.as_slice()),
+            (Timeframe::minutes(1), minutes.as_slice()),
+        ];
+
+        let events = cdl().confluence(&[Pattern::Doji], &series, 2).unwrap();
+
+        assert!(!events.is_empty());
+        for event in &events {
+            assert_eq!(
+                event.timeframes(),
+                vec![Timeframe::minutes(3), Timeframe::minutes(1)]
+            );
+            assert_eq!(event.end - event.start, 180_000);
+
+            let anchor = event.signals[0].detection.index;
+            for signal in &event.signals[1..] {
+                assert!((3 * anchor..3 * anchor + 3).contains(&signal.detection.index));
+            }
+        }
+
+        assert!(cdl()
+            .confluence(&[Pattern::Doji], &series, 3)
+            .unwrap()
+            .is_empty());
+    }
+}
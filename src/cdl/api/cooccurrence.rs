@@ -0,0 +1,182 @@
+use crate::{Detection, Pattern};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Counts of one pattern being accompanied or followed by another.
+///
+/// Returned from [`co_occurrence()`] and [`transitions()`]. Every detection of the
+/// pattern `from` is an event, and the event counts for each pattern `to` found at
+/// least once in the window of candles around it. Rows and columns cover all of
+/// [`Pattern::all()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatrix {
+    window: RangeInclusive<usize>,
+    occurrences: Vec<usize>,
+    counts: Vec<Vec<usize>>,
+}
+
+impl PatternMatrix {
+    /// Returns the offsets of the candles after an event that are searched, e.g. `0..=0`
+    /// for the same candle.
+    pub fn window(&self) -> RangeInclusive<usize> {
+        self.window.clone()
+    }
+
+    /// Returns the number of detections of the pattern.
+    pub fn occurrences(&self, pattern: Pattern) -> usize {
+        self.occurrences[Self::position(pattern)]
+    }
+
+    /// Returns the number of detections of `from` with `to` found in the window.
+    pub fn count(&self, from: Pattern, to: Pattern) -> usize {
+        self.counts[Self::position(from)][Self::position(to)]
+    }
+
+    /// Returns the share of the detections of `from` with `to` found in the window,
+    /// i.e. the probability of `to` given `from`.
+    ///
+    /// Returns `None` if `from` was not detected.
+    pub fn probability(&self, from: Pattern, to: Pattern) -> Option<f64> {
+        match self.occurrences(from) {
+            0 => None,
+            occurrences => Some(self.count(from, to) as f64 / occurrences as f64),
+        }
+    }
+
+    /// Returns the pairs of patterns with a non-zero count, ordered by `from` and then
+    /// by `to` in the order of [`Pattern::all()`].
+    pub fn pairs(&self) -> impl Iterator<Item = (Pattern, Pattern, usize)> + '_ {
+        let patterns = Pattern::all();
+
+        self.counts.iter().enumerate().flat_map(move |(from, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &count)| count > 0)
+                .map(move |(to, &count)| (patterns[from], patterns[to], count))
+        })
+    }
+
+    fn build(detections: &[Detection], window: RangeInclusive<usize>) -> Self {
+        let size = Pattern::all().len();
+        let mut by_candle: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for detection in detections {
+            let found = by_candle.entry(detection.index).or_default();
+            let position = Self::position(detection.pattern);
+            if !found.contains(&position) {
+                found.push(position);
+            }
+        }
+
+        let mut occurrences = vec![0; size];
+        let mut counts = vec![vec![0; size]; size];
+        for (&index, found) in &by_candle {
+            let mut seen = vec![false; size];
+            let last = index.saturating_add(*window.end());
+            if let Some(first) = index
+                .checked_add(*window.start())
+                .filter(|&first| first <= last)
+            {
+                for &position in by_candle.range(first..=last).flat_map(|(_, found)| found) {
+                    seen[position] = true;
+                }
+            }
+
+            for &from in found {
+                occurrences[from] += 1;
+                for (to, _) in seen.iter().enumerate().filter(|(_, &seen)| seen) {
+                    counts[from][to] += 1;
+                }
+            }
+        }
+
+        Self {
+            window,
+            occurrences,
+            counts,
+        }
+    }
+
+    fn position(pattern: Pattern) -> usize {
+        Pattern::all()
+            .iter()
+            .position(|&other| other == pattern)
+            .expect("every pattern is listed in Pattern::all()")
+    }
+}
+
+/// Counts the patterns found at the same candle.
+///
+/// The `detections` are the hits returned from [`crate::Cdl::detect_all`], typically
+/// for [`Pattern::all()`]. The diagonal of the result holds the number of detections of
+/// each pattern.
+pub fn co_occurrence(detections: &[Detection]) -> PatternMatrix {
+    PatternMatrix::build(detections, 0..=0)
+}
+
+/// Counts the patterns found within `max_lag` candles after each pattern.
+///
+/// For example, [`PatternMatrix::probability`] of `(Doji, BearishEngulfing)` with a
+/// `max_lag` of 3 is the share of `Doji` detections followed by a `BearishEngulfing`
+/// 1 to 3 candles later. A `max_lag` of 0 gives an empty matrix.
+pub fn transitions(detections: &[Detection], max_lag: usize) -> PatternMatrix {
+    PatternMatrix::build(detections, 1..=max_lag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pattern::*;
+
+    #[test]
+    fn test_co_occurrence() {
+        let detections = [
+            Detection::ending_at(Doji, 1, 1),
+            Detection::ending_at(SpinningTop, 1, 1),
+            Detection::ending_at(Doji, 5, 1),
+            Detection::ending_at(Hammer, 7, 1),
+        ];
+
+        let matrix = co_occurrence(&detections);
+
+        assert_eq!(matrix.occurrences(Doji), 2);
+        assert_eq!(matrix.count(Doji, Doji), 2);
+        assert_eq!(matrix.count(Doji, SpinningTop), 1);
+        assert_eq!(matrix.count(SpinningTop, Doji), 1);
+        assert_eq!(matrix.probability(Doji, SpinningTop), Some(0.5));
+        assert_eq!(matrix.probability(SpinningTop, Doji), Some(1.0));
+        assert_eq!(matrix.probability(Gravestone, Doji), None);
+        assert_eq!(matrix.pairs().count(), 5);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let detections = [
+            Detection::ending_at(Doji, 1, 1),
+            Detection::ending_at(BearishEngulfing, 3, 1),
+            Detection::ending_at(BearishEngulfing, 4, 1),
+            Detection::ending_at(Doji, 10, 1),
+            Detection::ending_at(BearishEngulfing, 14, 1),
+        ];
+
+        let matrix = transitions(&detections, 3);
+
+        assert_eq!(matrix.window(), 1..=3);
+        // Two engulfings within the window of the first doji count once.
+        assert_eq!(matrix.count(Doji, BearishEngulfing), 1);
+        assert_eq!(matrix.probability(Doji, BearishEngulfing), Some(0.5));
+        assert_eq!(matrix.count(BearishEngulfing, BearishEngulfing), 1);
+        assert_eq!(matrix.count(BearishEngulfing, Doji), 0);
+        assert_eq!(
+            matrix.pairs().collect::<Vec<_>>(),
+            vec![
+                (Doji, BearishEngulfing, 1),
+                (BearishEngulfing, BearishEngulfing, 1)
+            ]
+        );
+        assert_eq!(transitions(&detections, 0).pairs().count(), 0);
+        assert_eq!(
+            transitions(&detections, usize::MAX).count(Doji, BearishEngulfing),
+            2
+        );
+    }
+}
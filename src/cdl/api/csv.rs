@@ -0,0 +1,372 @@
+use crate::Error::{InvalidCandle, InvalidInput};
+use crate::{Error, SimpleCandle, Timestamp};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// A reference to a column of a CSV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// A column with the given header.
+    Name(String),
+    /// A column at the given zero-based position.
+    Index(usize),
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.into())
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+/// The format of the timestamp column of a CSV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateFormat {
+    /// Milliseconds since the Unix epoch.
+    UnixMillis,
+    /// Seconds since the Unix epoch.
+    UnixSeconds,
+    /// An RFC 3339 date and time with an offset, e.g. `2025-09-10T10:00:00+03:00`.
+    Rfc3339,
+    /// A `chrono` format string of a date and time in UTC, e.g. `%Y-%m-%d %H:%M:%S`.
+    ///
+    /// A format without time fields, e.g. `%Y-%m-%d`, parses dates at midnight.
+    Custom(String),
+}
+
+impl DateFormat {
+    /// Parses a timestamp in this format.
+    ///
+    /// Returns `None` if the value does not match the format or is out of the range of
+    /// [`Timestamp`].
+    pub fn parse(&self, value: &str) -> Option<Timestamp> {
+        match self {
+            DateFormat::UnixMillis => value.parse().ok(),
+            DateFormat::UnixSeconds => value
+                .parse::<i64>()
+                .ok()
+                .and_then(|seconds| seconds.checked_mul(1000)),
+            DateFormat::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|date| date.timestamp_millis()),
+            DateFormat::Custom(format) => NaiveDateTime::parse_from_str(value, format)
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(value, format)
+                        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+                })
+                .ok()
+                .map(|date| date.and_utc().timestamp_millis()),
+        }
+    }
+}
+
+/// Describes the layout of a CSV file read by [`CsvReader`].
+///
+/// Use `CsvFormat::default()` for a comma-separated file with the `open`, `high`,
+/// `low` and `close` headers, then modify fields as needed.
+#[derive(Debug, Clone)]
+pub struct CsvFormat {
+    /// The column of the opening price. Default is `"open"`.
+    pub open: Column,
+    /// The column of the highest price. Default is `"high"`.
+    pub high: Column,
+    /// The column of the lowest price. Default is `"low"`.
+    pub low: Column,
+    /// The column of the closing price. Default is `"close"`.
+    pub close: Column,
+    /// The column of the opening time, if any. Default is `None`.
+    pub timestamp: Option<Column>,
+    /// The format of the timestamp column. Default is [`DateFormat::UnixMillis`].
+    pub date_format: DateFormat,
+    /// The column of the traded volume, if any. Default is `None`.
+    pub volume: Option<Column>,
+    /// The field delimiter. Default is `b','`.
+    pub delimiter: u8,
+    /// Whether the first line is a header. Default is `true`.
+    pub has_headers: bool,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self {
+            open: "open".into(),
+            high: "high".into(),
+            low: "low".into(),
+            close: "close".into(),
+            timestamp: None,
+            date_format: DateFormat::UnixMillis,
+            volume: None,
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+}
+
+struct Positions {
+    open: usize,
+    high: usize,
+    low: usize,
+    close: usize,
+    timestamp: Option<usize>,
+    volume: Option<usize>,
+}
+
+/// A streaming reader of candles from CSV data.
+///
+/// The reader is an iterator yielding one [`SimpleCandle`] per record, so files of any
+/// size can be processed without loading them into memory.
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{cdl, Column, CsvFormat, CsvReader, Pattern};
+///
+/// let data = "date,o,h,l,c\n2025-09-10,100,103,99,102\n2025-09-11,102,104,101,101.5\n";
+/// let format = CsvFormat {
+///     open: "o".into(),
+///     high: "h".into(),
+///     low: "l".into(),
+///     close: "c".into(),
+///     timestamp: Some(Column::Index(0)),
+///     date_format: oxi_talib::DateFormat::Custom("%Y-%m-%d".into()),
+///     ..Default::default()
+/// };
+///
+/// let candles = CsvReader::new(data.as_bytes(), format)
+///     .unwrap()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// let signals = cdl().pattern(Pattern::Doji, &candles).unwrap();
+/// assert_eq!(signals.len(), 2);
+/// ```
+pub struct CsvReader<R: Read> {
+    records: ::csv::StringRecordsIntoIter<R>,
+    positions: Positions,
+    date_format: DateFormat,
+}
+
+impl CsvReader<File> {
+    /// Opens a CSV file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the file cannot be opened or a column of the
+    /// format is not found in its header.
+    pub fn from_path<P: AsRef<Path>>(path: P, format: CsvFormat) -> Result<Self, Error> {
+        let file = File::open(path).map_err(|e| InvalidInput(e.to_string()))?;
+
+        Self::new(file, format)
+    }
+}
+
+impl<R: Read> CsvReader<R> {
+    /// Creates a reader of CSV data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if the header cannot be read or a column of the
+    /// format is not found in it.
+    pub fn new(reader: R, format: CsvFormat) -> Result<Self, Error> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(format.delimiter)
+            .has_headers(format.has_headers)
+            .flexible(true)
+            .from_reader(reader);
+
+        let headers = if format.has_headers {
+            Some(
+                reader
+                    .headers()
+                    .map_err(|e| InvalidInput(e.to_string()))?
+                    .clone(),
+            )
+        } else {
+            None
+        };
+        let position = |column: &Column| match column {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => headers
+                .as_ref()
+                .and_then(|headers| headers.iter().position(|header| header.trim() == name))
+                .ok_or_else(|| InvalidInput(format!("column \"{name}\" is not found"))),
+        };
+
+        let positions = Positions {
+            open: position(&format.open)?,
+            high: position(&format.high)?,
+            low: position(&format.low)?,
+            close: position(&format.close)?,
+            timestamp: format.timestamp.as_ref().map(position).transpose()?,
+            volume: format.volume.as_ref().map(position).transpose()?,
+        };
+
+        Ok(Self {
+            records: reader.into_records(),
+            positions,
+            date_format: format.date_format,
+        })
+    }
+
+    fn candle(&self, record: &::csv::StringRecord) -> Result<SimpleCandle, String> {
+        let field = |position: usize| {
+            record
+                .get(position)
+                .map(str::trim)
+                .ok_or_else(|| format!("column #{position} is missing"))
+        };
+        let number = |position: usize| {
+            let value = field(position)?;
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("\"{value}\" is not a number"))
+        };
+
+        let p = &self.positions;
+        let mut candle = SimpleCandle::try_new(
+            number(p.open)?,
+            number(p.close)?,
+            number(p.high)?,
+            number(p.low)?,
+        )
+        .map_err(|e| e.to_string())?;
+        if let Some(position) = p.timestamp {
+            let value = field(position)?;
+            let timestamp = self
+                .date_format
+                .parse(value)
+                .ok_or_else(|| format!("\"{value}\" is not a valid date"))?;
+            candle = candle.with_timestamp(timestamp);
+        }
+        if let Some(position) = p.volume {
+            candle = candle
+                .try_with_volume(number(position)?)
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(candle)
+    }
+}
+
+impl<R: Read> Iterator for CsvReader<R> {
+    type Item = Result<SimpleCandle, Error>;
+
+    /// Reads the next candle.
+    ///
+    /// Invalid records produce an [`Error::InvalidCandle`] naming the line number,
+    /// after which reading can continue with the next record.
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(InvalidInput(e.to_string()))),
+        };
+        let line = record.position().map_or(0, |position| position.line());
+
+        Some(
+            self.candle(&record)
+                .map_err(|reason| InvalidCandle(format!("line {line}: {reason}"))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Candle;
+
+    #[test]
+    fn test_read_by_position_without_headers() {
+        let data = "1700000000;100;103;99;102;1500\n1700000060;102;104;101;101.5;900\n";
+        let format = CsvFormat {
+            open: 1.into(),
+            high: 2.into(),
+            low: 3.into(),
+            close: 4.into(),
+            timestamp: Some(0.into()),
+            date_format: DateFormat::UnixSeconds,
+            volume: Some(5.into()),
+            delimiter: b';',
+            has_headers: false,
+        };
+
+        let candles: Vec<_> = CsvReader::new(data.as_bytes(), format)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[1].close(), 101.5);
+        assert_eq!(candles[1].timestamp(), Some(1_700_000_060_000));
+        assert_eq!(candles[0].volume(), Some(1500.0));
+    }
+
+    #[test]
+    fn test_dates() {
+        let data = "time,open,high,low,close\n2025-09-10T10:00:00+03:00,1,1,1,1\n";
+        let format = CsvFormat {
+            timestamp: Some("time".into()),
+            date_format: DateFormat::Rfc3339,
+            ..Default::default()
+        };
+        let candle = CsvReader::new(data.as_bytes(), format)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(candle.timestamp(), Some(1_757_487_600_000));
+
+        let data = "time,open,high,low,close\n2025-09-10,1,1,1,1\n";
+        let format = CsvFormat {
+            timestamp: Some("time".into()),
+            date_format: DateFormat::Custom("%Y-%m-%d".into()),
+            ..Default::default()
+        };
+        let candle = CsvReader::new(data.as_bytes(), format)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(candle.timestamp(), Some(1_757_462_400_000));
+
+        let data = format!("time,open,high,low,close\n{},1,1,1,1\n", i64::MAX / 100);
+        let format = CsvFormat {
+            timestamp: Some("time".into()),
+            date_format: DateFormat::UnixSeconds,
+            ..Default::default()
+        };
+        let result = CsvReader::new(data.as_bytes(), format)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert!(matches!(result, Err(InvalidCandle(r)) if r.contains("is not a valid date")));
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let data = "open,high,low,close\n100,103,99,102\n100,98,99,102\n100,x,99,102\n";
+
+        let results: Vec<_> = CsvReader::new(data.as_bytes(), CsvFormat::default())
+            .unwrap()
+            .collect();
+
+        assert!(results[0].is_ok());
+        assert!(matches!(&results[1], Err(InvalidCandle(r)) if r.starts_with("line 3:")));
+        assert!(matches!(&results[2], Err(InvalidCandle(r)) if r.starts_with("line 4:")));
+
+        let format = CsvFormat {
+            volume: Some("volume".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            CsvReader::new(data.as_bytes(), format),
+            Err(InvalidInput(_))
+        ));
+    }
+}
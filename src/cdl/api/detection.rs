@@ -0,0 +1,95 @@
+use crate::{Candle, Pattern, Signal, Timestamp};
+
+/// A pattern found at a specific candle.
+///
+/// Instances of this struct are returned from [`crate::Cdl::detect`] and
+/// [`crate::Cdl::detect_all`]. Direct construction by a user is not intended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detection {
+    /// The index of the candle in the input slice.
+    pub index: usize,
+    /// The timestamp of the candle, if the [`Candle`] provides one.
+    pub timestamp: Option<Timestamp>,
+    /// The detected pattern.
+    pub pattern: Pattern,
+    /// The signal produced for the candle.
+    pub signal: Signal,
+}
+
+impl Detection {
+    pub(crate) fn collect<'a, C: Candle>(
+        pattern: Pattern,
+        candles: &'a [C],
+        signals: &'a [Option<Signal>],
+    ) -> impl Iterator<Item = Detection> + 'a {
+        signals
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, signal)| {
+                signal.map(|signal| Detection {
+                    index,
+                    timestamp: candles[index].timestamp(),
+                    pattern,
+                    signal,
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+impl Detection {
+    /// A test detection whose signal spans `len` candles ending at `index`.
+    pub(crate) fn ending_at(pattern: Pattern, index: usize, len: usize) -> Self {
+        Detection {
+            index,
+            timestamp: None,
+            pattern,
+            signal: Signal::try_new(50, crate::Span::ending_at(index, len)).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pattern::*;
+    use crate::{cdl, SimpleCandle};
+
+    #[test]
+    fn test_detect_uses_candle_timestamps() {
+        let data: Vec<(f64, f64, f64, f64)> = vec![
+            (2871.0, 2967.0, 2850.0, 2885.5),
+            (2896.5, 2906.5, 2805.5, 2816.0),
+            (2819.0, 2846.5, 2815.0, 2820.0),
+            (2825.5, 2830.0, 2789.5, 2811.0),
+            (2800.0, 2867.0, 2755.0, 2860.0),
+            (2865.0, 2922.0, 2847.5, 2909.5),
+            (2910.5, 2940.0, 2905.5, 2922.0),
+            (2931.0, 2984.5, 2925.5, 2932.0),
+            (2934.0, 3000.0, 2934.0, 2994.0),
+            (3029.0, 3075.0, 3025.5, 3069.0),
+            (3077.5, 3083.0, 3069.0, 3079.5), // Doji
+            (3070.0, 3130.0, 3031.5, 3075.0), // Doji
+        ];
+        let day = 86_400_000;
+        let candles: Vec<SimpleCandle> = data
+            .into_iter()
+            .enumerate()
+            .map(|(i, (open, high, low, close))| {
+                SimpleCandle::try_new(open, close, high, low)
+                    .unwrap()
+                    .with_timestamp(i as i64 * day)
+            })
+            .collect();
+
+        let detections = cdl().detect(Doji, &candles).unwrap();
+
+        let found: Vec<_> = detections
+            .iter()
+            .map(|d| (d.index, d.timestamp, d.pattern))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(10, Some(10 * day), Doji), (11, Some(11 * day), Doji)]
+        );
+    }
+}
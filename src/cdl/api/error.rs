@@ -2,7 +2,10 @@ use crate::cdl::api::error::Error::*;
 use std::fmt::Formatter;
 
 /// An enum representing all possible errors in the library.
+///
+/// New variants may be added in minor releases, so matches on it need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// An error from the underlying calculation engine.
     CalculationError(String),
@@ -10,6 +13,14 @@ pub enum Error {
     AlreadyConfigured,
     /// Provided candle data was invalid (e.g., `high < low`).
     InvalidCandle(String),
+    /// A pattern name could not be parsed.
+    UnknownPattern(String),
+    /// A candle index was out of range or had too few candles before it.
+    InvalidIndex(String),
+    /// A timeframe had a non-positive duration or an offset outside of a day.
+    InvalidTimeframe(String),
+    /// Input data could not be read or parsed.
+    InvalidInput(String),
 }
 
 impl std::fmt::Display for Error {
@@ -18,6 +29,10 @@ impl std::fmt::Display for Error {
             CalculationError(r) => write!(f, "Calculation error: {r}"),
             AlreadyConfigured => write!(f, "Already Configured"),
             InvalidCandle(r) => write!(f, "Invalid Candle: {r}"),
+            UnknownPattern(r) => write!(f, "Unknown Pattern: {r}"),
+            InvalidIndex(r) => write!(f, "Invalid Index: {r}"),
+            InvalidTimeframe(r) => write!(f, "Invalid Timeframe: {r}"),
+            InvalidInput(r) => write!(f, "Invalid Input: {r}"),
         }
     }
 }
@@ -0,0 +1,110 @@
+use crate::Pattern;
+use std::fmt::{Display, Formatter};
+
+/// How a measured value is compared with its threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// The value must be less than the threshold.
+    Less,
+    /// The value must be less than or equal to the threshold.
+    LessOrEqual,
+    /// The value must be greater than the threshold.
+    Greater,
+    /// The value must be greater than or equal to the threshold.
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub(crate) fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        })
+    }
+}
+
+/// The result of evaluating a single rule of a pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCheck {
+    /// A short description of the rule, e.g. `"lower shadow long"`.
+    pub rule: &'static str,
+    /// The value measured on the candles.
+    pub value: f64,
+    /// The threshold the value is compared with.
+    pub threshold: f64,
+    /// The rolling average the threshold is derived from, for rules based on
+    /// the average size of the preceding candles.
+    pub average: Option<f64>,
+    /// How `value` is compared with `threshold`.
+    pub comparison: Comparison,
+    /// Whether the rule is satisfied.
+    pub passed: bool,
+}
+
+impl Display for RuleCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {} {} {}",
+            if self.passed { "pass" } else { "fail" },
+            self.rule,
+            self.value,
+            self.comparison,
+            self.threshold
+        )?;
+        if let Some(average) = self.average {
+            write!(f, " (average {average})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A breakdown of the decision made for a pattern at a specific candle.
+///
+/// Instances of this struct are returned from [`crate::Cdl::explain`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The pattern evaluated.
+    pub pattern: Pattern,
+    /// The index of the candle the pattern was evaluated at.
+    pub index: usize,
+    /// Whether the pattern was detected at the candle.
+    pub detected: bool,
+    /// The rules of the pattern, in the order they are checked.
+    pub rules: Vec<RuleCheck>,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} at candle #{}: {}",
+            self.pattern,
+            self.index,
+            if self.detected {
+                "detected"
+            } else {
+                "not detected"
+            }
+        )?;
+        for rule in &self.rules {
+            writeln!(f, "  {rule}")?;
+        }
+
+        Ok(())
+    }
+}
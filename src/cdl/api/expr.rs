@@ -0,0 +1,329 @@
+use crate::{Candle, Cdl, Error, Pattern, Quality, Signal, SimpleCandle, Span};
+use std::collections::HashMap;
+use std::ops::Not;
+
+/// The signals of each pattern and whether it could not be evaluated at each candle.
+type Scans = HashMap<Pattern, (Vec<Option<Signal>>, Vec<bool>)>;
+
+/// A compound rule over pattern signals, evaluated by [`Cdl::expression`].
+///
+/// Expressions are built from [`Expr::pattern`] and combined with [`Expr::and`],
+/// [`Expr::or`], [`Expr::then_within`] and the `!` operator. A compound signal spans
+/// all the candles of the signals it was built from and has the lowest of their
+/// qualities. Its trend is confirmed only if it is confirmed for all of them, and its
+/// volume ratio is the lowest of theirs.
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{Expr, Pattern::*};
+///
+/// // A Doji followed within 2 candles by a Bearish Engulfing, unless it completes
+/// // Three White Soldiers.
+/// let expr = Expr::pattern(Doji)
+///     .then_within(2, Expr::pattern(BearishEngulfing))
+///     .and(!Expr::pattern(ThreeWhiteSoldiers));
+///
+/// assert_eq!(expr.patterns(), vec![Doji, BearishEngulfing, ThreeWhiteSoldiers]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// The signals of a pattern.
+    Pattern(Pattern),
+    /// Both expressions match at the same candle.
+    And(Box<Expr>, Box<Expr>),
+    /// Either expression matches at the candle. If both match, the signal of higher
+    /// quality is taken.
+    Or(Box<Expr>, Box<Expr>),
+    /// The expression does not match at the candle, and all of its patterns are
+    /// evaluated there, i.e. not within the warm-up or [`crate::Evaluation::Unavailable`].
+    /// The result is a one-candle signal of the highest quality, so it does not lower
+    /// the quality of a conjunction.
+    Not(Box<Expr>),
+    /// The second expression matches at the candle and the first one matched at one of
+    /// the given number of candles before it. The most recent match of the first
+    /// expression is taken.
+    ThenWithin(Box<Expr>, usize, Box<Expr>),
+}
+
+impl Expr {
+    /// Creates an expression matching the signals of the pattern.
+    pub fn pattern(pattern: Pattern) -> Self {
+        Expr::Pattern(pattern)
+    }
+
+    /// Returns an expression matching where both `self` and `other` match.
+    pub fn and(self, other: Expr) -> Self {
+        Expr::And(Box::new(self), Box::new(other))
+    }
+
+    /// Returns an expression matching where `self` or `other` matches.
+    pub fn or(self, other: Expr) -> Self {
+        Expr::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns an expression matching where `next` matches 1 to `bars` candles after
+    /// `self` matched.
+    pub fn then_within(self, bars: usize, next: Expr) -> Self {
+        Expr::ThenWithin(Box::new(self), bars, Box::new(next))
+    }
+
+    /// Returns the distinct patterns the expression refers to, in the order they
+    /// appear.
+    pub fn patterns(&self) -> Vec<Pattern> {
+        let mut patterns = Vec::new();
+        self.collect_patterns(&mut patterns);
+        patterns
+    }
+
+    fn collect_patterns(&self, patterns: &mut Vec<Pattern>) {
+        match self {
+            Expr::Pattern(pattern) => {
+                if !patterns.contains(pattern) {
+                    patterns.push(*pattern);
+                }
+            }
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::ThenWithin(a, _, b) => {
+                a.collect_patterns(patterns);
+                b.collect_patterns(patterns);
+            }
+            Expr::Not(a) => a.collect_patterns(patterns),
+        }
+    }
+
+    fn evaluate(&self, scans: &Scans) -> Vec<Option<Signal>> {
+        match self {
+            Expr::Pattern(pattern) => scans[pattern].0.clone(),
+            Expr::And(a, b) => a
+                .evaluate(scans)
+                .into_iter()
+                .zip(b.evaluate(scans))
+                .map(|(a, b)| Some(merge(a?, b?)))
+                .collect(),
+            Expr::Or(a, b) => a
+                .evaluate(scans)
+                .into_iter()
+                .zip(b.evaluate(scans))
+                .map(|(a, b)| match (a, b) {
+                    (Some(a), Some(b)) if b.quality > a.quality => Some(b),
+                    (a, b) => a.or(b),
+                })
+                .collect(),
+            Expr::Not(a) => a
+                .evaluate(scans)
+                .into_iter()
+                .zip(a.unevaluated(scans))
+                .enumerate()
+                .map(|(index, (signal, unevaluated))| match signal {
+                    Some(_) => None,
+                    None if unevaluated => None,
+                    None => Quality::try_new(Quality::MAX)
+                        .map(|quality| Signal::new(quality, Span::ending_at(index, 1))),
+                })
+                .collect(),
+            Expr::ThenWithin(a, bars, b) => {
+                let first = a.evaluate(scans);
+                b.evaluate(scans)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, signal)| {
+                        let earlier = first[index.saturating_sub(*bars)..index]
+                            .iter()
+                            .rev()
+                            .find_map(|signal| *signal)?;
+                        Some(merge(earlier, signal?))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns whether a pattern of the expression could not be evaluated at each
+    /// candle.
+    fn unevaluated(&self, scans: &Scans) -> Vec<bool> {
+        match self {
+            Expr::Pattern(pattern) => scans[pattern].1.clone(),
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::ThenWithin(a, _, b) => a
+                .unevaluated(scans)
+                .into_iter()
+                .zip(b.unevaluated(scans))
+                .map(|(a, b)| a || b)
+                .collect(),
+            Expr::Not(a) => a.unevaluated(scans),
+        }
+    }
+}
+
+impl Not for Expr {
+    type Output = Expr;
+
+    /// Returns an expression matching where `self` does not match.
+    fn not(self) -> Self::Output {
+        Expr::Not(Box::new(self))
+    }
+}
+
+/// Combines two signals into one spanning the candles of both.
+fn merge(first: Signal, second: Signal) -> Signal {
+    let (a, b) = (first.span(), second.span());
+    let start = a.start.min(b.start);
+    let end = a.end().max(b.end());
+
+    let signal = Signal::new(
+        first.quality.min(second.quality),
+        Span {
+            start,
+            len: end + 1 - start,
+        },
+    );
+    let signal = match first.confirmed_at().max(second.confirmed_at()) {
+        Some(index) => signal.with_confirmed_at(index),
+        None => signal,
+    };
+    let signal = match (first.trend_confirmed(), second.trend_confirmed()) {
+        (Some(a), Some(b)) => signal.with_trend_confirmed(a && b),
+        (Some(confirmed), None) | (None, Some(confirmed)) => signal.with_trend_confirmed(confirmed),
+        (None, None) => signal,
+    };
+    match (first.volume_ratio(), second.volume_ratio()) {
+        (Some(a), Some(b)) => signal.with_volume_ratio(a.min(b)),
+        (Some(ratio), None) | (None, Some(ratio)) => signal.with_volume_ratio(ratio),
+        (None, None) => signal,
+    }
+}
+
+impl Cdl {
+    /// Scans a slice of candles for a compound rule over several patterns.
+    ///
+    /// Returns a `Vec<Option<Signal>>` of the same length as the input `candles`
+    /// slice, like [`Cdl::pattern()`]. Each pattern of the expression is scanned only
+    /// once, however often it appears in it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`].
+    pub fn expression<C: Candle>(
+        &self,
+        expr: &Expr,
+        candles: &[C],
+    ) -> Result<Vec<Option<Signal>>, Error> {
+        let candles = candles
+            .iter()
+            .map(|candle| SimpleCandle::try_from_candle(candle.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut scans = HashMap::new();
+        for pattern in expr.patterns() {
+            scans.insert(pattern, self.evaluated_signals(pattern, &candles)?);
+        }
+
+        Ok(expr.evaluate(&scans))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cdl;
+    use crate::Pattern::*;
+
+    fn signal(score: u8, end: usize, len: usize) -> Option<Signal> {
+        Signal::try_new(score, Span::ending_at(end, len))
+    }
+
+    /// Scans of patterns evaluated at every candle.
+    fn scans(signals: Vec<(Pattern, Vec<Option<Signal>>)>) -> Scans {
+        signals
+            .into_iter()
+            .map(|(pattern, signals)| {
+                let unevaluated = vec![false; signals.len()];
+                (pattern, (signals, unevaluated))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let signals = scans(vec![
+            (Doji, vec![None, signal(40, 1, 1), None, None, None]),
+            (
+                BearishEngulfing,
+                vec![None, None, None, signal(70, 3, 2), signal(20, 4, 2)],
+            ),
+            (
+                ThreeWhiteSoldiers,
+                vec![None, None, None, None, signal(90, 4, 3)],
+            ),
+        ]);
+
+        let sequence = Expr::pattern(Doji).then_within(2, Expr::pattern(BearishEngulfing));
+        let found = sequence.evaluate(&signals);
+        assert_eq!(found[..3], [None, None, None]);
+        let at = found[3].unwrap();
+        assert_eq!(at.quality.value(), 40);
+        assert_eq!(at.span(), Span { start: 1, len: 3 });
+        assert_eq!(found[4], None);
+
+        let filtered = Expr::pattern(BearishEngulfing).and(!Expr::pattern(ThreeWhiteSoldiers));
+        let found = filtered.evaluate(&signals);
+        assert_eq!(found[3], signal(70, 3, 2));
+        assert_eq!(found[4], None);
+
+        let either = Expr::pattern(BearishEngulfing).or(Expr::pattern(ThreeWhiteSoldiers));
+        let found = either.evaluate(&signals);
+        assert_eq!(found[3], signal(70, 3, 2));
+        assert_eq!(found[4], signal(90, 4, 3));
+    }
+
+    #[test]
+    fn test_not_requires_evaluated_patterns() {
+        let mut signals = scans(vec![(Doji, vec![None, None, signal(40, 2, 1)])]);
+        signals.get_mut(&Doji).unwrap().1[0] = true;
+
+        let found = (!Expr::pattern(Doji)).evaluate(&signals);
+
+        assert_eq!(found[0], None);
+        assert_eq!(found[1], signal(100, 1, 1));
+        assert_eq!(found[2], None);
+    }
+
+    #[test]
+    fn test_merge_keeps_annotations() {
+        let first = signal(40, 1, 1)
+            .unwrap()
+            .with_trend_confirmed(true)
+            .with_volume_ratio(2.0);
+        let second = signal(70, 2, 1)
+            .unwrap()
+            .with_trend_confirmed(false)
+            .with_volume_ratio(1.5);
+
+        let merged = merge(first, second);
+        assert_eq!(merged.trend_confirmed(), Some(false));
+        assert_eq!(merged.volume_ratio(), Some(1.5));
+
+        let merged = merge(first, signal(70, 2, 1).unwrap());
+        assert_eq!(merged.trend_confirmed(), Some(true));
+        assert_eq!(merged.volume_ratio(), Some(2.0));
+    }
+
+    #[test]
+    fn test_expression_matches_patterns() {
+        let candles: Vec<_> = (0..30)
+            .map(|i| {
+                let base = 100.0 + (i % 5) as f64;
+                SimpleCandle::try_new(base, base + 0.02, base + 1.0, base - 1.0).unwrap()
+            })
+            .collect();
+
+        let doji = cdl().pattern(Doji, &candles).unwrap();
+        let expr = Expr::pattern(Doji).or(Expr::pattern(Doji).and(Expr::pattern(Doji)));
+
+        assert!(doji.iter().any(Option::is_some));
+        assert_eq!(cdl().expression(&expr, &candles).unwrap(), doji);
+        let not_doji = cdl().expression(&!Expr::pattern(Doji), &candles).unwrap();
+        assert_eq!(not_doji[29].is_some(), doji[29].is_none());
+        // TA-Lib does not evaluate the first candles.
+        assert_eq!(not_doji[0], None);
+    }
+}
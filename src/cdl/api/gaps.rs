@@ -0,0 +1,257 @@
+use crate::Error::{InvalidCandle, InvalidTimeframe};
+use crate::{Candle, Error, Pattern, Signal, Timeframe, Timestamp};
+
+/// A hole in the data: one or more candles missing between two consecutive candles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataGap {
+    /// The index of the first candle after the hole.
+    pub index: usize,
+    /// The timestamp of the candle before the hole.
+    pub from: Timestamp,
+    /// The timestamp of the candle after the hole.
+    pub to: Timestamp,
+    /// The number of candles missing.
+    pub missing: usize,
+}
+
+/// The result of evaluating a pattern at a candle, returned from
+/// [`crate::Cdl::evaluate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evaluation {
+    /// The pattern was found at the candle.
+    Detected(Signal),
+    /// The pattern was not found at the candle.
+    NotDetected,
+    /// The candles forming the pattern span a hole in the data, so the pattern was
+    /// not evaluated.
+    Unavailable,
+}
+
+/// A check for holes in timestamped data.
+///
+/// Gap patterns such as `BullishKicking` compare consecutive candles, so missing data
+/// looks like a market gap. With a check set via [`crate::Cdl::with_gap_check`], the
+/// patterns whose candles span a [`DataGap`] are not reported.
+///
+/// A hole is found when the timestamps of consecutive candles are more than `interval`
+/// apart. When a `session` timeframe is given, only holes inside of a period are found:
+/// the step from one period to the next, e.g. an overnight gap between
+/// [`Timeframe::Day`]s, is a real market gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapCheck {
+    /// The expected interval between the timestamps of consecutive candles, in
+    /// milliseconds.
+    pub interval: i64,
+    /// The periods within which the candles are expected to be continuous.
+    pub session: Option<Timeframe>,
+}
+
+impl GapCheck {
+    /// Creates a check of candles following each other every `interval` milliseconds.
+    pub fn new(interval: i64) -> Self {
+        Self {
+            interval,
+            session: None,
+        }
+    }
+
+    /// Returns the check with the given session timeframe.
+    pub fn with_session(self, session: Timeframe) -> Self {
+        Self {
+            session: Some(session),
+            ..self
+        }
+    }
+
+    /// Finds the holes in the candles.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCandle`] if a candle has no timestamp or the timestamps
+    /// are not in ascending order, and [`Error::InvalidTimeframe`] if the interval is
+    /// not positive or the session is invalid.
+    pub fn find<C: Candle>(&self, candles: &[C]) -> Result<Vec<DataGap>, Error> {
+        if self.interval <= 0 {
+            return Err(InvalidTimeframe(format!("interval {}", self.interval)));
+        }
+        if let Some(session) = self.session {
+            session.validate()?;
+        }
+
+        let timestamps = candles
+            .iter()
+            .enumerate()
+            .map(|(index, candle)| {
+                candle
+                    .timestamp()
+                    .ok_or_else(|| InvalidCandle(format!("candle #{index} has no timestamp")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut gaps = Vec::new();
+        for (index, pair) in timestamps.windows(2).enumerate() {
+            let (from, to) = (pair[0], pair[1]);
+            if to <= from {
+                return Err(InvalidCandle(format!(
+                    "candle #{} is not after the previous candle",
+                    index + 1
+                )));
+            }
+            if to - from <= self.interval || !self.same_period(from, to) {
+                continue;
+            }
+
+            gaps.push(DataGap {
+                index: index + 1,
+                from,
+                to,
+                missing: ((to - from - 1) / self.interval) as usize,
+            });
+        }
+
+        Ok(gaps)
+    }
+
+    /// Returns, for each candle, whether a pattern ending at it spans a hole.
+    pub(crate) fn unavailable(pattern: Pattern, gaps: &[DataGap], len: usize) -> Vec<bool> {
+        let mut unavailable = vec![false; len];
+        for gap in gaps {
+            let last = (gap.index + pattern.candle_count() - 1).min(len);
+            unavailable[gap.index..last].fill(true);
+        }
+
+        unavailable
+    }
+
+    fn same_period(&self, from: Timestamp, to: Timestamp) -> bool {
+        match self.session {
+            Some(session) => {
+                session.period_start(from).is_some()
+                    && session.period_start(from) == session.period_start(to)
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cdl, Confirmation, ConfirmationRule, SimpleCandle};
+
+    const MINUTE: i64 = 60_000;
+
+    fn candles_at(minutes: &[i64]) -> Vec<SimpleCandle> {
+        minutes
+            .iter()
+            .map(|&minute| {
+                SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0)
+                    .unwrap()
+                    .with_timestamp(minute * MINUTE)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find() {
+        let candles = candles_at(&[0, 1, 2, 5, 6, 8]);
+
+        let gaps = GapCheck::new(MINUTE).find(&candles).unwrap();
+
+        assert_eq!(
+            gaps,
+            vec![
+                DataGap {
+                    index: 3,
+                    from: 2 * MINUTE,
+                    to: 5 * MINUTE,
+                    missing: 2,
+                },
+                DataGap {
+                    index: 5,
+                    from: 6 * MINUTE,
+                    to: 8 * MINUTE,
+                    missing: 1,
+                },
+            ]
+        );
+
+        let unavailable = GapCheck::unavailable(Pattern::BullishEngulfing, &gaps, 6);
+        assert_eq!(unavailable, vec![false, false, false, true, false, true]);
+    }
+
+    #[test]
+    fn test_pattern_across_hole_is_unavailable() {
+        let mut candles: Vec<_> = (0..11)
+            .map(|i| {
+                let open = 10.0 + (i % 2) as f64 * 0.2;
+                SimpleCandle::try_new(open, 10.1, 10.4, 9.8)
+                    .unwrap()
+                    .with_timestamp(i * MINUTE)
+            })
+            .collect();
+        candles.push(
+            SimpleCandle::try_new(10.5, 10.0, 10.6, 9.9)
+                .unwrap()
+                .with_timestamp(11 * MINUTE),
+        );
+        candles.push(
+            SimpleCandle::try_new(9.9, 10.7, 10.8, 9.8)
+                .unwrap()
+                .with_timestamp(15 * MINUTE),
+        );
+        candles.push(
+            SimpleCandle::try_new(10.7, 11.0, 11.1, 10.6)
+                .unwrap()
+                .with_timestamp(16 * MINUTE),
+        );
+
+        let evaluations = cdl().evaluate(Pattern::BullishEngulfing, &candles).unwrap();
+        assert!(matches!(evaluations[12], Evaluation::Detected(_)));
+
+        let analyzer = cdl().with_gap_check(GapCheck::new(MINUTE));
+        let evaluations = analyzer
+            .evaluate(Pattern::BullishEngulfing, &candles)
+            .unwrap();
+        assert_eq!(evaluations[12], Evaluation::Unavailable);
+        assert_eq!(evaluations[11], Evaluation::NotDetected);
+        assert!(analyzer
+            .pattern(Pattern::BullishEngulfing, &candles)
+            .unwrap()
+            .iter()
+            .all(Option::is_none));
+
+        // A confirmed signal is reported, and unavailable, at the confirming candle.
+        let confirming = analyzer.with_confirmation(Confirmation {
+            bars: 1,
+            rule: ConfirmationRule::Color,
+        });
+        let evaluations = confirming
+            .evaluate(Pattern::BullishEngulfing, &candles)
+            .unwrap();
+        assert_eq!(evaluations[12], Evaluation::NotDetected);
+        assert_eq!(evaluations[13], Evaluation::Unavailable);
+        let scores = confirming
+            .scores(Pattern::BullishEngulfing, &candles)
+            .unwrap();
+        assert_eq!(scores[12..], [Some(0), None]);
+    }
+
+    #[test]
+    fn test_session_gaps_are_not_holes() {
+        let session = Timeframe::Session {
+            open: 0,
+            duration: 10 * MINUTE,
+        };
+        let day = 24 * 60;
+        let candles = candles_at(&[8, 9, day, day + 1, day + 3]);
+
+        let gaps = GapCheck::new(MINUTE)
+            .with_session(session)
+            .find(&candles)
+            .unwrap();
+
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].index, 4);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Candle, Error, SimpleCandle};
+
+/// Converts a series of candles into Heikin-Ashi candles.
+///
+/// The result has the same length as `candles` and can be passed straight to
+/// [`crate::Cdl::pattern`]. Timestamps and volumes of the source candles are kept.
+/// See [`HeikinAshi`] for the formulas and for converting candles one at a time.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidCandle`] if a source candle is invalid.
+pub fn heikin_ashi<C: Candle>(candles: &[C]) -> Result<Vec<SimpleCandle>, Error> {
+    let mut transformer = HeikinAshi::new();
+
+    candles
+        .iter()
+        .map(|candle| transformer.push(candle))
+        .collect()
+}
+
+/// A streaming converter of candles into Heikin-Ashi candles.
+///
+/// Each Heikin-Ashi candle is calculated from the source candle and the previous
+/// Heikin-Ashi candle:
+///
+/// * close = (open + high + low + close) / 4
+/// * open = (previous open + previous close) / 2, or (open + close) / 2 for the first
+///   candle
+/// * high = the maximum of the high, the Heikin-Ashi open and close
+/// * low = the minimum of the low, the Heikin-Ashi open and close
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{Candle, HeikinAshi, SimpleCandle};
+///
+/// let mut transformer = HeikinAshi::new();
+/// let candle = SimpleCandle::try_new(10.0, 12.0, 13.0, 9.0).unwrap();
+///
+/// let ha = transformer.push(&candle).unwrap();
+/// assert_eq!(ha.open(), 11.0);
+/// assert_eq!(ha.close(), 11.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeikinAshi {
+    previous: Option<(f64, f64)>,
+}
+
+impl HeikinAshi {
+    /// Creates a converter with no previous candle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts the next candle of the series.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCandle`] if the candle is invalid. The state of the
+    /// converter is not changed in this case.
+    pub fn push<C: Candle>(&mut self, candle: &C) -> Result<SimpleCandle, Error> {
+        let source = SimpleCandle::try_from_candle(candle.clone())?;
+
+        let close = (source.open() + source.high() + source.low() + source.close()) / 4.0;
+        let open = match self.previous {
+            Some((open, close)) => (open + close) / 2.0,
+            None => (source.open() + source.close()) / 2.0,
+        };
+        let high = source.high().max(open).max(close);
+        let low = source.low().min(open).min(close);
+
+        let mut ha = SimpleCandle::try_new(open, close, high, low)?;
+        if let Some(timestamp) = source.timestamp() {
+            ha = ha.with_timestamp(timestamp);
+        }
+        if let Some(volume) = source.volume() {
+            ha = ha.try_with_volume(volume)?;
+        }
+
+        self.previous = Some((open, close));
+
+        Ok(ha)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cdl, Pattern};
+
+    #[test]
+    fn test_heikin_ashi() {
+        let candles = vec![
+            SimpleCandle::try_new(10.0, 12.0, 13.0, 9.0)
+                .unwrap()
+                .with_timestamp(1_000),
+            SimpleCandle::try_new(12.0, 14.0, 15.0, 11.0)
+                .unwrap()
+                .try_with_volume(500.0)
+                .unwrap(),
+        ];
+
+        let ha = heikin_ashi(&candles).unwrap();
+
+        assert_eq!(ha.len(), 2);
+        assert_eq!(
+            ha[0],
+            SimpleCandle::try_new(11.0, 11.0, 13.0, 9.0)
+                .unwrap()
+                .with_timestamp(1_000)
+        );
+        assert_eq!(
+            ha[1],
+            SimpleCandle::try_new(11.0, 13.0, 15.0, 11.0)
+                .unwrap()
+                .try_with_volume(500.0)
+                .unwrap()
+        );
+
+        assert!(cdl().pattern(Pattern::Doji, &ha).is_ok());
+    }
+
+    #[test]
+    fn test_streaming() {
+        let candles = [
+            (10.0, 12.0, 13.0, 9.0),
+            (12.0, 14.0, 15.0, 11.0),
+            (14.0, 12.0, 14.5, 10.5),
+            (12.0, 13.0, 13.5, 11.5),
+        ]
+        .map(|(open, close, high, low)| SimpleCandle::try_new(open, close, high, low).unwrap());
+        let expected = [
+            (11.0, 11.0, 13.0, 9.0),
+            (11.0, 13.0, 15.0, 11.0),
+            (12.0, 12.75, 14.5, 10.5),
+            (12.375, 12.5, 13.5, 11.5),
+        ]
+        .map(|(open, close, high, low)| SimpleCandle::try_new(open, close, high, low).unwrap());
+
+        let mut transformer = HeikinAshi::new();
+        let streamed: Vec<_> = candles
+            .iter()
+            .map(|candle| transformer.push(candle).unwrap())
+            .collect();
+
+        assert_eq!(streamed, expected);
+        assert_eq!(heikin_ashi(&candles).unwrap(), expected);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::cdl::api::error::Error;
+use crate::cdl::api::patterns::Pattern;
+use crate::cdl::api::patterns::Pattern::*;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A direction of the market.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trend {
+    /// Rising prices.
+    Up,
+    /// Falling prices.
+    Down,
+}
+
+/// The classification of a pattern by its expected outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternKind {
+    /// The pattern signals a reversal of the prior trend.
+    Reversal,
+    /// The pattern signals a continuation of the current move.
+    Continuation,
+    /// The pattern signals indecision between buyers and sellers.
+    Indecision,
+}
+
+/// The expected direction of the price move after a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bias {
+    /// Prices are expected to rise.
+    Bullish,
+    /// Prices are expected to fall.
+    Bearish,
+    /// No direction is implied.
+    Neutral,
+}
+
+/// Static metadata describing a [`Pattern`].
+///
+/// Obtained from [`Pattern::info()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternInfo {
+    /// The pattern described.
+    pub pattern: Pattern,
+    /// The human-readable name, e.g. `"Three White Soldiers"`.
+    pub name: &'static str,
+    /// The traditional Japanese name, e.g. `"Aka Sanpei"`.
+    pub japanese_name: &'static str,
+    /// The number of candles forming the pattern.
+    pub candle_count: usize,
+    /// The trend that should precede the pattern, if any.
+    pub prior_trend: Option<Trend>,
+    /// The classification of the pattern.
+    pub kind: PatternKind,
+    /// The expected direction of the price move.
+    pub bias: Bias,
+}
+
+impl Pattern {
+    const ALL: [Pattern; 28] = [
+        Hammer,
+        InvertedHammer,
+        ThreeWhiteSoldiers,
+        MorningStar,
+        PiercingLine,
+        DragonFly,
+        HangingMan,
+        ShootingStar,
+        ThreeBlackCrows,
+        EveningStar,
+        DarkCloudCover,
+        Gravestone,
+        Doji,
+        SpinningTop,
+        BullishEngulfing,
+        BullishHarami,
+        BullishHaramiCross,
+        BullishMarubozu,
+        BullishLongLine,
+        BullishShortLine,
+        BullishKicking,
+        BearishEngulfing,
+        BearishHarami,
+        BearishHaramiCross,
+        BearishMarubozu,
+        BearishLongLine,
+        BearishShortLine,
+        BearishKicking,
+    ];
+
+    /// Returns all supported patterns, in declaration order.
+    pub fn all() -> &'static [Pattern] {
+        &Self::ALL
+    }
+
+    /// Returns the static metadata of the pattern.
+    pub fn info(&self) -> PatternInfo {
+        PatternInfo {
+            pattern: *self,
+            name: self.name(),
+            japanese_name: self.japanese_name(),
+            candle_count: self.count(),
+            prior_trend: self.prior_trend(),
+            kind: self.kind(),
+            bias: self.bias(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Hammer => "Hammer",
+            InvertedHammer => "Inverted Hammer",
+            ThreeWhiteSoldiers => "Three White Soldiers",
+            MorningStar => "Morning Star",
+            PiercingLine => "Piercing Line",
+            DragonFly => "Dragonfly Doji",
+            HangingMan => "Hanging Man",
+            ShootingStar => "Shooting Star",
+            ThreeBlackCrows => "Three Black Crows",
+            EveningStar => "Evening Star",
+            DarkCloudCover => "Dark Cloud Cover",
+            Gravestone => "Gravestone Doji",
+            Doji => "Doji",
+            SpinningTop => "Spinning Top",
+            BullishEngulfing => "Bullish Engulfing",
+            BullishHarami => "Bullish Harami",
+            BullishHaramiCross => "Bullish Harami Cross",
+            BullishMarubozu => "Bullish Marubozu",
+            BullishLongLine => "Bullish Long Line",
+            BullishShortLine => "Bullish Short Line",
+            BullishKicking => "Bullish Kicking",
+            BearishEngulfing => "Bearish Engulfing",
+            BearishHarami => "Bearish Harami",
+            BearishHaramiCross => "Bearish Harami Cross",
+            BearishMarubozu => "Bearish Marubozu",
+            BearishLongLine => "Bearish Long Line",
+            BearishShortLine => "Bearish Short Line",
+            BearishKicking => "Bearish Kicking",
+        }
+    }
+
+    fn japanese_name(&self) -> &'static str {
+        match self {
+            Hammer => "Takuri",
+            InvertedHammer => "Tonkachi",
+            ThreeWhiteSoldiers => "Aka Sanpei",
+            MorningStar => "Sankawa Ake no Myojyo",
+            PiercingLine => "Kirikomi",
+            DragonFly => "Tonbo",
+            HangingMan => "Kubitsuri",
+            ShootingStar => "Nagare Boshi",
+            ThreeBlackCrows => "Sanba Garasu",
+            EveningStar => "Sankawa Yoi no Myojyo",
+            DarkCloudCover => "Kabuse",
+            Gravestone => "Tohba",
+            Doji => "Doji",
+            SpinningTop => "Koma",
+            BullishEngulfing | BearishEngulfing => "Tsutsumi",
+            BullishHarami | BearishHarami => "Harami",
+            BullishHaramiCross | BearishHaramiCross => "Harami Yose Sen",
+            BullishMarubozu => "Yo no Marubozu",
+            BearishMarubozu => "In no Marubozu",
+            BullishLongLine => "Dai Yosen",
+            BearishLongLine => "Dai Insen",
+            BullishShortLine => "Sho Yosen",
+            BearishShortLine => "Sho Insen",
+            BullishKicking | BearishKicking => "Keri Ashi",
+        }
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            Hammer | InvertedHammer | DragonFly | HangingMan | ShootingStar | Gravestone | Doji
+            | SpinningTop | BullishMarubozu | BullishLongLine | BullishShortLine
+            | BearishMarubozu | BearishLongLine | BearishShortLine => 1,
+
+            PiercingLine | DarkCloudCover | BullishEngulfing | BullishHarami
+            | BullishHaramiCross | BullishKicking | BearishEngulfing | BearishHarami
+            | BearishHaramiCross | BearishKicking => 2,
+
+            ThreeWhiteSoldiers | MorningStar | ThreeBlackCrows | EveningStar => 3,
+        }
+    }
+
+    fn kind(&self) -> PatternKind {
+        match self {
+            Doji | SpinningTop | BullishShortLine | BearishShortLine => PatternKind::Indecision,
+            BullishMarubozu | BullishLongLine | BearishMarubozu | BearishLongLine => {
+                PatternKind::Continuation
+            }
+            _ => PatternKind::Reversal,
+        }
+    }
+
+    fn bias(&self) -> Bias {
+        match self {
+            Hammer | InvertedHammer | ThreeWhiteSoldiers | MorningStar | PiercingLine
+            | DragonFly | BullishEngulfing | BullishHarami | BullishHaramiCross
+            | BullishMarubozu | BullishLongLine | BullishKicking => Bias::Bullish,
+
+            HangingMan | ShootingStar | ThreeBlackCrows | EveningStar | DarkCloudCover
+            | Gravestone | BearishEngulfing | BearishHarami | BearishHaramiCross
+            | BearishMarubozu | BearishLongLine | BearishKicking => Bias::Bearish,
+
+            Doji | SpinningTop | BullishShortLine | BearishShortLine => Bias::Neutral,
+        }
+    }
+
+    fn prior_trend(&self) -> Option<Trend> {
+        match (self.kind(), self.bias()) {
+            (PatternKind::Reversal, Bias::Bullish) => Some(Trend::Down),
+            (PatternKind::Reversal, Bias::Bearish) => Some(Trend::Up),
+            _ => None,
+        }
+    }
+
+    fn normalize(name: &str) -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+}
+
+impl Display for Pattern {
+    /// Writes the human-readable name of the pattern, e.g. `"Three White Soldiers"`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.info().name)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    /// Parses a pattern from its human-readable name or its variant name.
+    ///
+    /// The comparison ignores case, spaces, underscores and hyphens, so
+    /// `"Three White Soldiers"`, `"ThreeWhiteSoldiers"` and `"three_white_soldiers"`
+    /// are all accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownPattern`] if the name does not match any pattern.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = Self::normalize(s);
+
+        Self::all()
+            .iter()
+            .find(|pattern| {
+                Self::normalize(pattern.info().name) == name
+                    || Self::normalize(&format!("{pattern:?}")) == name
+            })
+            .copied()
+            .ok_or_else(|| Error::UnknownPattern(s.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_and_from_str_round_trip() {
+        for &pattern in Pattern::all() {
+            assert_eq!(pattern.to_string().parse::<Pattern>().unwrap(), pattern);
+            assert_eq!(format!("{pattern:?}").parse::<Pattern>().unwrap(), pattern);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "three_white_soldiers".parse::<Pattern>().unwrap(),
+            ThreeWhiteSoldiers
+        );
+        assert_eq!("dragonfly doji".parse::<Pattern>().unwrap(), DragonFly);
+        assert!("Abandoned Baby".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn test_info() {
+        assert_eq!(Pattern::all().len(), 28);
+
+        let info = MorningStar.info();
+        assert_eq!(info.pattern, MorningStar);
+        assert_eq!(info.candle_count, 3);
+        assert_eq!(info.prior_trend, Some(Trend::Down));
+        assert_eq!(info.kind, PatternKind::Reversal);
+        assert_eq!(info.bias, Bias::Bullish);
+    }
+}
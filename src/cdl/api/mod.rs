@@ -1,20 +1,83 @@
+/// Forward-return statistics of detected patterns.
+pub mod analytics;
+/// Conversions from and to Apache Arrow, available with the `arrow` feature.
+#[cfg(feature = "arrow")]
+pub mod arrow;
+/// A deterministic `Backtest` of trading on pattern detections.
+pub mod backtest;
 /// Candle types and traits.
 pub mod candles;
 /// The main `Cdl` analyzer struct.
 pub mod cdl;
+/// The `Confirmation` stage for reversal signals.
+pub mod confirmation;
+/// Multi-timeframe `Confluence` events.
+pub mod confluence;
+/// Co-occurrence and transition statistics between patterns.
+pub mod cooccurrence;
+/// The `CsvReader` of candles, available with the `csv` feature.
+#[cfg(feature = "csv")]
+pub mod csv;
+/// The `Detection` struct.
+pub mod detection;
 /// Error types for the library.
 pub mod error;
+/// The `Explanation` of a pattern decision.
+pub mod explain;
+/// Composable `Expr` rules over pattern signals.
+pub mod expr;
+/// The `GapCheck` for holes in timestamped data.
+pub mod gaps;
+/// The Heikin-Ashi candle transformation.
+pub mod heikin_ashi;
+/// Static `PatternInfo` metadata for each `Pattern`.
+pub mod info;
 /// The `Pattern` enum.
 pub mod patterns;
+/// The `ToPrice` conversion trait for candle prices.
+pub mod price;
+/// The `CdlDataFrame` extension, available with the `polars` feature.
+#[cfg(feature = "polars")]
+pub mod polars;
+/// The incremental `Recognizer` of streamed candles.
+pub mod recognizer;
+/// Resampling of candles into higher `Timeframe`s.
+pub mod resample;
 /// Configuration `Settings` struct.
 pub mod settings;
 /// `Signal` and `Quality` structs.
 pub mod signal;
+/// The `TrendFilter` for reversal patterns.
+pub mod trend;
+/// The `VolumeFilter` for volume-confirmed patterns.
+pub mod volume;
 
 pub use crate::cdl::engines::talib::engine::configure;
+pub use analytics::*;
+#[cfg(feature = "arrow")]
+pub use arrow::*;
+pub use backtest::*;
 pub use candles::*;
 pub use cdl::*;
+pub use confirmation::*;
+pub use confluence::*;
+pub use cooccurrence::*;
+#[cfg(feature = "csv")]
+pub use csv::*;
+pub use detection::*;
 pub use error::*;
+pub use explain::*;
+pub use expr::*;
+pub use gaps::*;
+pub use heikin_ashi::*;
+pub use info::*;
 pub use patterns::*;
+#[cfg(feature = "polars")]
+pub use polars::*;
+pub use price::*;
+pub use recognizer::*;
+pub use resample::*;
 pub use settings::*;
 pub use signal::*;
+pub use trend::*;
+pub use volume::*;
@@ -1,5 +1,6 @@
 /// An enumeration of all supported candlestick patterns.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pattern {
     // --- Single directional patterns ---
     /// Hammer
@@ -67,3 +68,13 @@ pub enum Pattern {
     /// Bearish Kicking
     BearishKicking,
 }
+
+impl Pattern {
+    /// Returns the number of candles forming the pattern.
+    ///
+    /// For example, `Hammer` is formed by a single candle, `BullishEngulfing` by two
+    /// and `MorningStar` by three.
+    pub fn candle_count(&self) -> usize {
+        self.info().candle_count
+    }
+}
@@ -0,0 +1,186 @@
+use crate::Error::{InvalidCandle, InvalidInput};
+use crate::{Cdl, Error, Pattern, SimpleCandle};
+use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+
+/// An extension of a Polars [`DataFrame`] with pattern columns.
+///
+/// The frame must have the `open`, `high`, `low` and `close` columns of a numeric
+/// type. Available with the `polars` feature.
+///
+/// Rows with a null price are skipped rather than rejected. They are not candles, so
+/// they do not count towards the warm-up, which then ends on a later row than in a
+/// frame without them.
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{CdlDataFrame, Pattern};
+/// use polars::prelude::*;
+///
+/// let df = df!(
+///     "open" => [10.0; 15],
+///     "high" => [11.0; 15],
+///     "low" => [9.0; 15],
+///     "close" => [10.0; 15],
+/// )
+/// .unwrap();
+///
+/// let df = df.cdl_patterns(&[Pattern::Doji, Pattern::Hammer]).unwrap();
+/// assert_eq!(df.width(), 6);
+/// ```
+pub trait CdlDataFrame {
+    /// Returns the frame with one column appended per pattern, scanned by
+    /// [`crate::cdl()`].
+    ///
+    /// See [`CdlDataFrame::cdl_patterns_with`] for the columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as
+    /// [`CdlDataFrame::cdl_patterns_with`].
+    fn cdl_patterns(&self, patterns: &[Pattern]) -> Result<DataFrame, Error> {
+        self.cdl_patterns_with(&crate::cdl(), patterns)
+    }
+
+    /// Returns the frame with one column appended per pattern, scanned by `analyzer`.
+    ///
+    /// Each column is a `UInt8` column named after the pattern variant (e.g.
+    /// `"BullishEngulfing"`) holding the values of [`Cdl::scores()`]: the quality of
+    /// the signal, `0` if the pattern was not found, and null for warm-up rows.
+    ///
+    /// Rows with a null price are skipped: their scores are null, and the rows around
+    /// them are scanned as consecutive candles.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] if a price column is missing or not numeric, or
+    /// the frame already has a column named after one of the patterns,
+    /// [`Error::InvalidCandle`] naming the row if the prices do not form a valid
+    /// candle, and an [`Error`] under the same conditions as [`Cdl::scores()`]
+    /// otherwise.
+    fn cdl_patterns_with(&self, analyzer: &Cdl, patterns: &[Pattern]) -> Result<DataFrame, Error>;
+}
+
+impl CdlDataFrame for DataFrame {
+    fn cdl_patterns_with(&self, analyzer: &Cdl, patterns: &[Pattern]) -> Result<DataFrame, Error> {
+        let (rows, candles): (Vec<_>, Vec<_>) = candles(self)?.into_iter().unzip();
+
+        let mut df = self.clone();
+        for &pattern in patterns {
+            let name = format!("{pattern:?}");
+            if df.column(&name).is_ok() {
+                return Err(InvalidInput(format!("column \"{name}\" already exists")));
+            }
+
+            let mut scores = vec![None; self.height()];
+            for (row, score) in rows.iter().zip(analyzer.scores(pattern, &candles)?) {
+                scores[*row] = score;
+            }
+            let column = Series::new(name.into(), scores);
+            df.with_column(column)
+                .map_err(|e| InvalidInput(e.to_string()))?;
+        }
+
+        Ok(df)
+    }
+}
+
+/// Returns the candles of the rows without a null price, with the index of their row.
+fn candles(df: &DataFrame) -> Result<Vec<(usize, SimpleCandle)>, Error> {
+    let price = |name: &str| -> Result<Vec<Option<f64>>, Error> {
+        let column = df
+            .column(name)
+            .and_then(|column| column.cast(&DataType::Float64))
+            .map_err(|e| InvalidInput(e.to_string()))?;
+        let values = column.f64().map_err(|e| InvalidInput(e.to_string()))?;
+
+        Ok(values.iter().collect())
+    };
+
+    let (open, high, low, close) = (
+        price("open")?,
+        price("high")?,
+        price("low")?,
+        price("close")?,
+    );
+
+    (0..df.height())
+        .filter_map(|row| {
+            let (Some(open), Some(high), Some(low), Some(close)) =
+                (open[row], high[row], low[row], close[row])
+            else {
+                return None;
+            };
+
+            Some(
+                SimpleCandle::try_new(open, close, high, low)
+                    .map(|candle| (row, candle))
+                    .map_err(|e| InvalidCandle(format!("row {row}: {e}"))),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::df;
+
+    #[test]
+    fn test_cdl_patterns() {
+        let df = df!(
+            "open" => [10i32; 15],
+            "high" => [11i32; 15],
+            "low" => [9i32; 15],
+            "close" => [10i32; 15],
+        )
+        .unwrap();
+
+        let result = df.cdl_patterns(&[Pattern::Doji]).unwrap();
+
+        let doji = result.column("Doji").unwrap().u8().unwrap();
+        assert_eq!(doji.get(0), None);
+        assert!(doji.get(14).unwrap() > 0);
+        assert_eq!(result.height(), 15);
+    }
+
+    #[test]
+    fn test_null_prices() {
+        let close: Vec<_> = (0..16).map(|row| (row != 3).then_some(10.0)).collect();
+        let mut df = df!(
+            "open" => [10.0; 16],
+            "high" => [11.0; 16],
+            "low" => [9.0; 16],
+        )
+        .unwrap();
+        df.with_column(Series::new("close".into(), close)).unwrap();
+
+        let result = df.cdl_patterns(&[Pattern::Doji]).unwrap();
+
+        let doji = result.column("Doji").unwrap().u8().unwrap();
+        assert_eq!(doji.get(3), None);
+        assert!(doji.get(15).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let df = df!("open" => [1.0], "high" => [1.0], "low" => [1.0]).unwrap();
+        assert!(matches!(
+            df.cdl_patterns(&[Pattern::Doji]),
+            Err(InvalidInput(_))
+        ));
+
+        let df = df!("open" => [1.0], "high" => [1.0], "low" => [1.0], "close" => [1.0]).unwrap();
+        let with_doji = df.cdl_patterns(&[Pattern::Doji]).unwrap();
+        assert!(matches!(
+            with_doji.cdl_patterns(&[Pattern::Doji]),
+            Err(InvalidInput(_))
+        ));
+
+        let df = df!("open" => [1.0], "high" => [1.0], "low" => [2.0], "close" => [1.0]).unwrap();
+        assert!(matches!(
+            df.cdl_patterns(&[Pattern::Doji]),
+            Err(InvalidCandle(_))
+        ));
+    }
+}
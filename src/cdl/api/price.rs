@@ -0,0 +1,101 @@
+/// A trait for numeric types that can be used as candle prices.
+///
+/// The conversion to `f64` is fallible, which allows decimal and fixed-point
+/// types (whose range or precision differs from `f64`) to be used as
+/// [`crate::Candle::Price`].
+///
+/// Implementations are provided for the primitive types that convert to `f64`
+/// losslessly and, with the `rust_decimal` feature, for `rust_decimal::Decimal`.
+pub trait ToPrice {
+    /// Converts the value to `f64`.
+    ///
+    /// Returns `None` if the value cannot be represented as `f64`.
+    fn to_f64(&self) -> Option<f64>;
+}
+
+macro_rules! impl_to_price {
+    ($($t:ty),*) => {
+        $(
+            impl ToPrice for $t {
+                fn to_f64(&self) -> Option<f64> {
+                    Some(f64::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_price!(f64, f32, i8, i16, i32, u8, u16, u32);
+
+#[cfg(feature = "rust_decimal")]
+impl ToPrice for rust_decimal::Decimal {
+    fn to_f64(&self) -> Option<f64> {
+        rust_decimal::prelude::ToPrimitive::to_f64(self)
+    }
+}
+
+#[cfg(all(test, feature = "rust_decimal"))]
+mod tests {
+    use super::*;
+    use crate::{cdl, Candle, Pattern, SimpleCandle};
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[derive(Clone)]
+    struct DecimalCandle(Decimal, Decimal, Decimal, Decimal);
+
+    impl Candle for DecimalCandle {
+        type Price = Decimal;
+
+        fn open(&self) -> Self::Price {
+            self.0
+        }
+
+        fn close(&self) -> Self::Price {
+            self.1
+        }
+
+        fn high(&self) -> Self::Price {
+            self.2
+        }
+
+        fn low(&self) -> Self::Price {
+            self.3
+        }
+    }
+
+    #[test]
+    fn test_decimal_prices() {
+        let d = |s: &str| Decimal::from_str(s).unwrap();
+
+        assert_eq!(d("3268.8").to_f64(), Some(3268.8));
+
+        let prices: Vec<_> = (0..30)
+            .map(|i| {
+                let base = 100 + i % 5;
+                let price = |offset: &str| d(&base.to_string()) + d(offset);
+                (price("0"), price("0.02"), price("1"), price("-1"))
+            })
+            .collect();
+        let candles: Vec<_> = prices
+            .iter()
+            .map(|&(open, close, high, low)| DecimalCandle(open, close, high, low))
+            .collect();
+        let floats: Vec<_> = prices
+            .iter()
+            .map(|(open, close, high, low)| {
+                let f = |price: &Decimal| price.to_f64().unwrap();
+                SimpleCandle::try_new(f(open), f(close), f(high), f(low)).unwrap()
+            })
+            .collect();
+
+        let signals = cdl().pattern(Pattern::Doji, &candles).unwrap();
+
+        assert!(signals[29].is_some());
+        assert_eq!(signals, cdl().pattern(Pattern::Doji, &floats).unwrap());
+        assert_eq!(
+            cdl().scores(Pattern::Doji, &candles).unwrap(),
+            cdl().scores(Pattern::Doji, &floats).unwrap()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{Candle, Cdl, Detection, Error, Pattern, SimpleCandle};
+
+/// An incremental recognizer of patterns in a stream of candles.
+///
+/// Candles are pushed one at a time as they close, and each push returns the patterns
+/// completed by that candle. Only the candles the analyzer needs to evaluate the last
+/// candle are kept, so a recognizer can run indefinitely. The detections are the same
+/// as those found by [`Cdl::detect_all`] on the whole series.
+///
+/// # Example
+///
+/// ```
+/// use oxi_talib::{cdl, Pattern, Recognizer, SimpleCandle};
+///
+/// let mut recognizer = Recognizer::new(cdl(), &[Pattern::Doji]);
+///
+/// for _ in 0..20 {
+///     let candle = SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0).unwrap();
+///     for detection in recognizer.push(candle).unwrap() {
+///         println!("{} at candle #{}", detection.pattern, detection.index);
+///     }
+/// }
+/// ```
+pub struct Recognizer {
+    analyzer: Cdl,
+    patterns: Vec<Pattern>,
+    history: usize,
+    candles: Vec<SimpleCandle>,
+    count: usize,
+}
+
+impl Recognizer {
+    /// Creates a recognizer of `patterns` using `analyzer`.
+    ///
+    /// Stages configured on the analyzer, such as a [`crate::Confirmation`], are applied
+    /// to the stream as well.
+    pub fn new(analyzer: Cdl, patterns: &[Pattern]) -> Self {
+        let history = analyzer.history();
+
+        Self {
+            analyzer,
+            patterns: patterns.to_vec(),
+            history,
+            candles: Vec::with_capacity(2 * history),
+            count: 0,
+        }
+    }
+
+    /// Returns the number of candles pushed so far.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if no candles have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Adds the next closed candle and returns the patterns found at it.
+    ///
+    /// The indices of the detections and their signals count all the candles pushed
+    /// since the recognizer was created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] under the same conditions as [`Cdl::pattern()`]. An invalid
+    /// candle is not added.
+    pub fn push<C: Candle>(&mut self, candle: C) -> Result<Vec<Detection>, Error> {
+        let candle = SimpleCandle::try_from_candle(candle)?;
+
+        if self.candles.len() == 2 * self.history {
+            self.candles.drain(..self.history);
+        }
+        self.candles.push(candle);
+        self.count += 1;
+
+        let last = self.candles.len() - 1;
+        let offset = self.count - self.candles.len();
+        let window = &self.candles[self.candles.len().saturating_sub(self.history)..];
+        let window_offset = self.candles.len() - window.len();
+
+        Ok(self
+            .analyzer
+            .detect_all(&self.patterns, window)?
+            .into_iter()
+            .filter(|detection| window_offset + detection.index == last)
+            .map(|detection| Detection {
+                index: offset + last,
+                signal: detection.signal.shifted(offset + window_offset),
+                ..detection
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cdl, Confirmation, ConfirmationRule};
+
+    fn candles(len: usize) -> Vec<SimpleCandle> {
+        let mut seed: u64 = 7;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let mut close = 100.0;
+        (0..len)
+            .map(|i| {
+                let open: f64 = close + (next() - 0.5) * 2.0;
+                close = open + (next() - 0.5) * 6.0 + [1.5, -1.5][i / 20 % 2];
+                let high = open.max(close) + next() * 2.0;
+                let low = open.min(close) - next() * 2.0;
+                SimpleCandle::try_new(open, close, high, low)
+                    .unwrap()
+                    .with_timestamp(i as i64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_matches_batch() {
+        let candles = candles(300);
+        let confirmation = Confirmation {
+            bars: 1,
+            rule: ConfirmationRule::Color,
+        };
+
+        for confirmation in [None, Some(confirmation)] {
+            let analyzer = || match confirmation {
+                Some(confirmation) => cdl().with_confirmation(confirmation),
+                None => cdl(),
+            };
+
+            let mut recognizer = Recognizer::new(analyzer(), Pattern::all());
+            let streamed: Vec<_> = candles
+                .iter()
+                .flat_map(|candle| recognizer.push(candle.clone()).unwrap())
+                .collect();
+
+            let batch = analyzer().detect_all(Pattern::all(), &candles).unwrap();
+
+            assert!(!batch.is_empty());
+            assert_eq!(streamed, batch);
+            assert_eq!(recognizer.len(), 300);
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use crate::Error::{InvalidCandle, InvalidTimeframe};
+use crate::{Candle, Detection, Error, SimpleCandle, Timestamp};
+use std::ops::Range;
+
+const MINUTE: i64 = 60_000;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+/// The Unix epoch was a Thursday, 3 days after the start of an ISO week.
+const EPOCH_WEEKDAY: i64 = 3 * DAY;
+
+/// A period that candles are aggregated into by [`resample`].
+///
+/// All durations and offsets are in milliseconds, like [`Timestamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timeframe {
+    /// Periods of a fixed duration aligned to the Unix epoch, e.g. 15 minutes.
+    Fixed(i64),
+    /// Calendar days starting at midnight in a time zone with the given UTC offset.
+    Day {
+        /// The offset of the time zone from UTC.
+        utc_offset: i64,
+    },
+    /// Calendar weeks starting on Monday midnight in a time zone with the given UTC
+    /// offset.
+    Week {
+        /// The offset of the time zone from UTC.
+        utc_offset: i64,
+    },
+    /// Trading sessions of a fixed length opening at the same time every day.
+    ///
+    /// Candles outside of the session are skipped.
+    Session {
+        /// The opening time of the session after midnight UTC.
+        open: i64,
+        /// The length of the session, at most a day.
+        duration: i64,
+    },
+}
+
+impl Timeframe {
+    /// Returns a fixed timeframe of `n` minutes.
+    ///
+    /// A number of minutes that does not fit into an `i64` of milliseconds gives an
+    /// invalid timeframe.
+    pub fn minutes(n: i64) -> Self {
+        Self::Fixed(n.checked_mul(MINUTE).unwrap_or(0))
+    }
+
+    /// Returns a fixed timeframe of `n` hours.
+    ///
+    /// A number of hours that does not fit into an `i64` of milliseconds gives an
+    /// invalid timeframe.
+    pub fn hours(n: i64) -> Self {
+        Self::Fixed(n.checked_mul(HOUR).unwrap_or(0))
+    }
+
+    /// Returns the start of the period containing `timestamp`, or `None` if the
+    /// timestamp is outside of a session or the period starts before the earliest
+    /// timestamp.
+    pub(crate) fn period_start(&self, timestamp: Timestamp) -> Option<Timestamp> {
+        match *self {
+            Self::Fixed(duration) => timestamp.checked_sub(timestamp.rem_euclid(duration)),
+            Self::Day { utc_offset } => {
+                timestamp.checked_sub((timestamp.checked_add(utc_offset)?).rem_euclid(DAY))
+            }
+            Self::Week { utc_offset } => {
+                let shifted = timestamp
+                    .checked_add(utc_offset)?
+                    .checked_add(EPOCH_WEEKDAY)?;
+                timestamp.checked_sub(shifted.rem_euclid(WEEK))
+            }
+            Self::Session { open, duration } => {
+                let start =
+                    timestamp.checked_sub((timestamp.checked_sub(open)?).rem_euclid(DAY))?;
+                (timestamp - start < duration).then_some(start)
+            }
+        }
+    }
+
+    /// Returns the end of the period starting at `start`, exclusive, or `None` if it is
+    /// after the latest timestamp.
+    pub(crate) fn period_end(&self, start: Timestamp) -> Option<Timestamp> {
+        match *self {
+            Self::Fixed(duration) | Self::Session { duration, .. } => start.checked_add(duration),
+            Self::Day { .. } => start.checked_add(DAY),
+            Self::Week { .. } => start.checked_add(WEEK),
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let valid = match *self {
+            Self::Fixed(duration) => duration > 0,
+            Self::Day { utc_offset } | Self::Week { utc_offset } => utc_offset.abs() < DAY,
+            Self::Session { open, duration } => {
+                (0..DAY).contains(&open) && (1..=DAY).contains(&duration)
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(InvalidTimeframe(format!("{self:?}")))
+        }
+    }
+}
+
+/// Candles aggregated into a higher [`Timeframe`].
+///
+/// Returned from [`resample`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resampled {
+    /// The aggregated candles, timestamped with the start of their period.
+    pub candles: Vec<SimpleCandle>,
+    /// The indices of the source candles forming each aggregated candle.
+    pub sources: Vec<Range<usize>>,
+}
+
+/// Aggregates timestamped candles into a higher timeframe.
+///
+/// Each period containing at least one candle produces a candle with the open of
+/// the first source candle, the close of the last one, the highest high and the lowest
+/// low. The volume is the sum of the source volumes if every source candle provides
+/// one. The last period may be incomplete if the data ends inside of it.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidCandle`] if a candle is invalid, has no timestamp or the
+/// timestamps are not in ascending order, and [`Error::InvalidTimeframe`] if the
+/// timeframe has a non-positive duration or an offset outside of a day.
+pub fn resample<C: Candle>(candles: &[C], timeframe: Timeframe) -> Result<Resampled, Error> {
+    timeframe.validate()?;
+
+    let mut periods: Vec<(Timestamp, Range<usize>)> = Vec::new();
+    let mut previous: Option<Timestamp> = None;
+    for (index, candle) in candles.iter().enumerate() {
+        let timestamp = candle
+            .timestamp()
+            .ok_or_else(|| InvalidCandle(format!("candle #{index} has no timestamp")))?;
+        if previous.is_some_and(|previous| timestamp <= previous) {
+            return Err(InvalidCandle(format!(
+                "candle #{index} is not after the previous candle"
+            )));
+        }
+        previous = Some(timestamp);
+
+        let Some(start) = timeframe.period_start(timestamp) else {
+            continue;
+        };
+        match periods.last_mut() {
+            Some((last, range)) if *last == start => range.end = index + 1,
+            _ => periods.push((start, index..index + 1)),
+        }
+    }
+
+    let candles = periods
+        .iter()
+        .map(|(start, range)| aggregate(&candles[range.clone()], *start))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Resampled {
+        candles,
+        sources: periods.into_iter().map(|(_, range)| range).collect(),
+    })
+}
+
+fn aggregate<C: Candle>(candles: &[C], start: Timestamp) -> Result<SimpleCandle, Error> {
+    let candles = candles
+        .iter()
+        .map(|candle| SimpleCandle::try_from_candle(candle.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let high = candles.iter().map(|c| c.high()).fold(f64::MIN, f64::max);
+    let low = candles.iter().map(|c| c.low()).fold(f64::MAX, f64::min);
+    let volume = candles.iter().map(|c| c.volume()).sum::<Option<f64>>();
+
+    let mut candle = SimpleCandle::try_new(
+        candles[0].open(),
+        candles[candles.len() - 1].close(),
+        high,
+        low,
+    )?
+    .with_timestamp(start);
+    if let Some(volume) = volume {
+        candle = candle.try_with_volume(volume)?;
+    }
+
+    Ok(candle)
+}
+
+/// A detection found on a higher timeframe and mapped back to the source candles.
+///
+/// Returned from [`crate::Cdl::detect_timeframes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeframeDetection {
+    /// The timeframe the pattern was found on.
+    pub timeframe: Timeframe,
+    /// The detection, indexed into the resampled candles.
+    pub detection: Detection,
+    /// The indices of the source candles forming the candle the pattern was found at.
+    ///
+    /// The pattern is known once the last of these candles closes.
+    pub source: Range<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pattern;
+
+    fn minute_candles(start: Timestamp, closes: &[f64]) -> Vec<SimpleCandle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                SimpleCandle::try_new(close - 1.0, close, close + 1.0, close - 2.0)
+                    .unwrap()
+                    .with_timestamp(start + i as i64 * MINUTE)
+                    .try_with_volume(10.0)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_fixed() {
+        let candles = minute_candles(0, &[10.0, 11.0, 12.0, 9.0, 8.0]);
+
+        let resampled = resample(&candles, Timeframe::minutes(3)).unwrap();
+
+        assert_eq!(resampled.sources, vec![0..3, 3..5]);
+        let first = &resampled.candles[0];
+        assert_eq!(
+            (first.open(), first.close(), first.high(), first.low()),
+            (9.0, 12.0, 13.0, 8.0)
+        );
+        assert_eq!(first.volume(), Some(30.0));
+        assert_eq!(resampled.candles[1].timestamp(), Some(3 * MINUTE));
+    }
+
+    #[test]
+    fn test_period_start() {
+        // 2024-01-03 (Wednesday) 10:30 UTC.
+        let timestamp = 1_704_277_800_000;
+        let monday = 1_704_067_200_000;
+
+        assert_eq!(
+            Timeframe::Day { utc_offset: 0 }.period_start(timestamp),
+            Some(monday + 2 * DAY)
+        );
+        assert_eq!(
+            Timeframe::Day {
+                utc_offset: 11 * HOUR
+            }
+            .period_start(timestamp),
+            Some(monday + 2 * DAY - 11 * HOUR)
+        );
+        assert_eq!(
+            Timeframe::Week { utc_offset: 0 }.period_start(timestamp),
+            Some(monday)
+        );
+
+        let session = Timeframe::Session {
+            open: 9 * HOUR,
+            duration: 8 * HOUR,
+        };
+        assert_eq!(
+            session.period_start(timestamp),
+            Some(monday + 2 * DAY + 9 * HOUR)
+        );
+        assert_eq!(session.period_start(timestamp - 2 * HOUR), None);
+    }
+
+    #[test]
+    fn test_extreme_timeframes() {
+        assert!(Timeframe::minutes(i64::MAX).validate().is_err());
+        assert!(Timeframe::hours(i64::MIN).validate().is_err());
+
+        let huge = Timeframe::Fixed(i64::MAX);
+        assert!(huge.validate().is_ok());
+        assert_eq!(huge.period_start(i64::MIN), None);
+        assert_eq!(huge.period_end(1), None);
+        assert_eq!(
+            Timeframe::Day { utc_offset: HOUR }.period_start(i64::MAX),
+            None
+        );
+        assert_eq!(
+            Timeframe::Week { utc_offset: 0 }.period_end(i64::MAX - DAY),
+            None
+        );
+
+        let candles = minute_candles(0, &[10.0, 11.0]);
+        assert_eq!(resample(&candles, huge).unwrap().candles.len(), 1);
+    }
+
+    #[test]
+    fn test_detect_timeframes_maps_to_source_candles() {
+        let candles: Vec<_> = (0..60)
+            .map(|i| {
+                SimpleCandle::try_new(10.0, 10.0, 11.0, 9.0)
+                    .unwrap()
+                    .with_timestamp(i * MINUTE)
+            })
+            .collect();
+
+        let detections = crate::cdl()
+            .detect_timeframes(&[Pattern::Doji], &candles, &[Timeframe::minutes(3)])
+            .unwrap();
+
+        assert!(!detections.is_empty());
+        for detection in detections {
+            let index = detection.detection.index;
+            assert_eq!(detection.source, 3 * index..3 * index + 3);
+            assert_eq!(
+                detection.detection.timestamp,
+                Some(3 * index as i64 * MINUTE)
+            );
+        }
+
+        let empty: Vec<SimpleCandle> = Vec::new();
+        assert!(crate::cdl()
+            .detect_timeframes(&[Pattern::Doji], &empty, &[Timeframe::minutes(3)])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resample_errors() {
+        let candles = minute_candles(0, &[10.0, 11.0]);
+
+        assert!(resample(&candles, Timeframe::Fixed(0)).is_err());
+        assert!(resample(
+            &[candles[1].clone(), candles[0].clone()],
+            Timeframe::hours(1)
+        )
+        .is_err());
+        assert!(resample(
+            &[SimpleCandle::try_new(1.0, 1.0, 1.0, 1.0).unwrap()],
+            Timeframe::hours(1)
+        )
+        .is_err());
+        // Sessions of consecutive days would overlap.
+        let session = |duration| Timeframe::Session { open: 0, duration };
+        assert!(resample(&candles, session(DAY)).is_ok());
+        assert!(resample(&candles, session(DAY + 1)).is_err());
+    }
+}
@@ -1,3 +1,16 @@
+use crate::{TrendFilter, VolumeFilter};
+
+/// What to do with a signal that fails a [`crate::TrendFilter`] or a
+/// [`crate::VolumeFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FilterAction {
+    /// Remove the signal from the results.
+    Suppress,
+    /// Keep the signal and report the result of the check on it.
+    Annotate,
+}
+
 /// Holds configuration parameters for the pattern recognition algorithms.
 ///
 /// These settings define how properties of candles, such as body size or
@@ -6,6 +19,8 @@
 /// Use `Settings::default()` to get standard values, then modify fields as needed.
 /// The created struct should be passed to [`crate::cdl::engines::talib::engine::configure`].
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Settings {
     /// The lookback period for calculating average candle component sizes.
     pub period: i32,
@@ -35,6 +50,10 @@ pub struct Settings {
     pub star_penetration_factor: f64,
     /// Penetration factor for `DarkCloudCover` and `PiercingLine` patterns. Default is 0.5.
     pub piercing_penetration_factor: f64,
+    /// An optional check of the trend preceding reversal patterns. Default is `None`.
+    pub trend_filter: Option<TrendFilter>,
+    /// An optional check of the volume of patterns. Default is `None`.
+    pub volume_filter: Option<VolumeFilter>,
 }
 
 impl Default for Settings {
@@ -58,6 +77,8 @@ impl Default for Settings {
             far_factor: 0.6,
             star_penetration_factor: 0.3,
             piercing_penetration_factor: 0.5,
+            trend_filter: None,
+            volume_filter: None,
         }
     }
 }
@@ -2,11 +2,17 @@
 ///
 /// The value is guaranteed to be between 1 and 100, inclusive.
 /// Construction fails if the input score is 0.
+///
+/// The score reflects how clearly a detection satisfies the rules of its pattern:
+/// each rule with a measurable threshold (body and shadow sizes, penetration, gaps)
+/// contributes the margin by which it exceeds the threshold derived from
+/// [`crate::Settings`]. A borderline detection scores close to 1, while a textbook
+/// one approaches 100.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Quality(u8);
 
 impl Quality {
-    const MAX: u8 = 100;
+    pub(crate) const MAX: u8 = 100;
 
     pub(crate) fn try_new(score: u8) -> Option<Self> {
         if score > 0 {
@@ -22,24 +28,141 @@ impl Quality {
     }
 }
 
+/// A range of consecutive candles forming a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The index of the first candle of the pattern.
+    pub start: usize,
+    /// The number of candles forming the pattern.
+    pub len: usize,
+}
+
+impl Span {
+    /// Creates a span of `len` candles ending at the candle with index `end`.
+    ///
+    /// The span is truncated if it would start before the first candle.
+    pub(crate) fn ending_at(end: usize, len: usize) -> Self {
+        let start = (end + 1).saturating_sub(len);
+
+        Self {
+            start,
+            len: end + 1 - start,
+        }
+    }
+
+    /// Returns the index of the last candle of the pattern.
+    pub fn end(&self) -> usize {
+        self.start + self.len - 1
+    }
+}
+
 /// A signal indicating a detected candlestick pattern.
 ///
 /// Instances of this struct are created by the library and returned
 /// from the [`crate::Cdl::pattern`] function. Direct construction by a user is not intended.
+///
+/// A signal is reported at the last candle of the pattern, or at the confirming candle
+/// when a [`crate::Confirmation`] is used. Use [`Signal::span`] to get all the candles
+/// forming it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Signal {
     /// The quality score of the signal, from 1 to 100.
     pub quality: Quality,
+    span: Span,
+    trend_confirmed: Option<bool>,
+    confirmed_at: Option<usize>,
+    // The bits of the `f64` ratio, so that the signal stays `Eq`. The ratio is never NaN.
+    volume_ratio: Option<u64>,
 }
 
 impl Signal {
-    pub(crate) fn new(quality: Quality) -> Self {
-        Self { quality }
+    pub(crate) fn new(quality: Quality, span: Span) -> Self {
+        Self {
+            quality,
+            span,
+            trend_confirmed: None,
+            confirmed_at: None,
+            volume_ratio: None,
+        }
     }
 
-    pub(crate) fn try_from_quality(score: u8) -> Option<Self> {
+    pub(crate) fn try_new(score: u8, span: Span) -> Option<Self> {
         let quality = Quality::try_new(score)?;
 
-        Some(Self::new(quality))
+        Some(Self::new(quality, span))
+    }
+
+    /// Returns the range of candles forming the detected pattern.
+    ///
+    /// The span ends at the candle the signal is reported for, and its length
+    /// is [`crate::Pattern::candle_count`] of the detected pattern.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the index of the candle at which the pattern was detected, i.e. the last
+    /// candle of its [`Signal::span`].
+    ///
+    /// It differs from the index the signal is reported at when a
+    /// [`crate::Confirmation`] is used.
+    pub fn detected_at(&self) -> usize {
+        self.span.end()
+    }
+
+    /// Returns the index of the candle that confirmed the pattern, or `None` if no
+    /// [`crate::Confirmation`] was applied to the signal.
+    pub fn confirmed_at(&self) -> Option<usize> {
+        self.confirmed_at
+    }
+
+    /// Returns whether the trend preceding the pattern matches the pattern's expected
+    /// [`crate::PatternInfo::prior_trend`].
+    ///
+    /// Returns `None` if no [`crate::TrendFilter`] is configured, the pattern has no
+    /// expected prior trend, or there are not enough candles before the pattern.
+    pub fn trend_confirmed(&self) -> Option<bool> {
+        self.trend_confirmed
+    }
+
+    pub(crate) fn with_trend_confirmed(self, confirmed: bool) -> Self {
+        Self {
+            trend_confirmed: Some(confirmed),
+            ..self
+        }
+    }
+
+    /// Returns the volume of the last candle of the pattern divided by the average
+    /// volume of the preceding candles.
+    ///
+    /// Returns `None` if no [`crate::VolumeFilter`] is configured or the candles do not
+    /// provide enough volume data.
+    pub fn volume_ratio(&self) -> Option<f64> {
+        self.volume_ratio.map(f64::from_bits)
+    }
+
+    pub(crate) fn with_volume_ratio(self, ratio: f64) -> Self {
+        Self {
+            volume_ratio: Some(ratio.to_bits()),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_confirmed_at(self, index: usize) -> Self {
+        Self {
+            confirmed_at: Some(index),
+            ..self
+        }
+    }
+
+    /// Returns the signal with the indices moved `offset` candles forward.
+    pub(crate) fn shifted(self, offset: usize) -> Self {
+        Self {
+            span: Span {
+                start: self.span.start + offset,
+                ..self.span
+            },
+            confirmed_at: self.confirmed_at.map(|index| index + offset),
+            ..self
+        }
     }
 }
@@ -0,0 +1,151 @@
+use crate::{Candle, Comparison, FilterAction, RuleCheck, SimpleCandle, Trend};
+
+/// A method for measuring the trend that precedes a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrendMethod {
+    /// The slope of the simple moving average of closing prices.
+    SmaSlope,
+    /// Consecutive lower lows for a downtrend, or higher highs for an uptrend.
+    HigherHighsLowerLows,
+    /// The slope of the least-squares regression line of closing prices.
+    LinearRegression,
+}
+
+/// A filter checking the trend that precedes reversal patterns.
+///
+/// TA-Lib does not check the prior trend, so a `Hammer` in the middle of an uptrend is
+/// reported like one after a decline. When a filter is set in
+/// [`crate::Settings::trend_filter`] or with [`crate::Cdl::with_trend_filter`], every
+/// pattern with a [`crate::PatternInfo::prior_trend`] is checked against the trend
+/// measured over the `window` candles before its first candle.
+///
+/// Signals that have fewer than `window + 1` candles before them (`window` for
+/// [`TrendMethod::LinearRegression`]) cannot be checked and are kept with
+/// [`crate::Signal::trend_confirmed`] returning `None`. The `window` must be at least 2,
+/// otherwise no signal is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrendFilter {
+    /// How the trend is measured.
+    pub method: TrendMethod,
+    /// The number of candles the trend is measured over.
+    pub window: usize,
+    /// What to do with a signal whose prior trend does not match.
+    pub action: FilterAction,
+}
+
+impl TrendFilter {
+    /// Checks that the candles before `start` form the `expected` trend.
+    ///
+    /// Returns `None` if there are not enough candles before `start`.
+    pub(crate) fn check(
+        &self,
+        candles: &[SimpleCandle],
+        start: usize,
+        expected: Trend,
+    ) -> Option<RuleCheck> {
+        // The slope methods compare each candle with the one before the window.
+        let needed = match self.method {
+            TrendMethod::LinearRegression => self.window,
+            TrendMethod::SmaSlope | TrendMethod::HigherHighsLowerLows => self.window + 1,
+        };
+        if self.window < 2 || start < needed || start > candles.len() {
+            return None;
+        }
+
+        let rule = match expected {
+            Trend::Up => "prior trend up",
+            Trend::Down => "prior trend down",
+        };
+        let direction = match expected {
+            Trend::Up => Comparison::Greater,
+            Trend::Down => Comparison::Less,
+        };
+
+        let (value, threshold, comparison) = match self.method {
+            TrendMethod::SmaSlope => {
+                // The SMA moves by the difference between the entering and leaving closes.
+                let entering = candles[start - 1].close();
+                let leaving = candles[start - self.window - 1].close();
+                ((entering - leaving) / self.window as f64, 0.0, direction)
+            }
+            TrendMethod::LinearRegression => (
+                Self::regression_slope(&candles[start - self.window..start]),
+                0.0,
+                direction,
+            ),
+            TrendMethod::HigherHighsLowerLows => {
+                let steps = candles[start - self.window - 1..start]
+                    .windows(2)
+                    .filter(|pair| match expected {
+                        Trend::Up => pair[1].high() > pair[0].high(),
+                        Trend::Down => pair[1].low() < pair[0].low(),
+                    })
+                    .count();
+                (steps as f64, self.window as f64, Comparison::GreaterOrEqual)
+            }
+        };
+
+        Some(RuleCheck {
+            rule,
+            value,
+            threshold,
+            average: None,
+            comparison,
+            passed: comparison.holds(value, threshold),
+        })
+    }
+
+    fn regression_slope(candles: &[SimpleCandle]) -> f64 {
+        let n = candles.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = candles.iter().map(|c| c.close()).sum::<f64>() / n;
+
+        let (covariance, variance) =
+            candles
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(covariance, variance), (x, candle)| {
+                    let dx = x as f64 - mean_x;
+                    (
+                        covariance + dx * (candle.close() - mean_y),
+                        variance + dx * dx,
+                    )
+                });
+
+        covariance / variance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_needs_method_specific_history() {
+        let candles: Vec<_> = (0..4)
+            .map(|i| {
+                let close = 10.0 - i as f64;
+                SimpleCandle::try_new(close, close, close + 0.5, close - 0.5).unwrap()
+            })
+            .collect();
+        let filter = |method| TrendFilter {
+            method,
+            window: 3,
+            action: FilterAction::Annotate,
+        };
+
+        let regression = filter(TrendMethod::LinearRegression).check(&candles, 3, Trend::Down);
+        assert!(regression.unwrap().passed);
+        assert!(filter(TrendMethod::SmaSlope)
+            .check(&candles, 3, Trend::Down)
+            .is_none());
+        assert!(
+            filter(TrendMethod::SmaSlope)
+                .check(&candles, 4, Trend::Down)
+                .unwrap()
+                .passed
+        );
+    }
+}
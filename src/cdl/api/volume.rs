@@ -0,0 +1,91 @@
+use crate::{Candle, Comparison, FilterAction, RuleCheck, SimpleCandle};
+
+/// A filter checking that a pattern is formed on above-average volume.
+///
+/// Patterns such as `BullishEngulfing` or `BearishKicking` are more reliable when the
+/// last candle of the pattern trades on heavy volume. When a filter is set in
+/// [`crate::Settings::volume_filter`] or with [`crate::Cdl::with_volume_filter`], the
+/// volume of the last candle of every pattern is compared with the average volume of
+/// the `period` candles before it, and the ratio is reported by
+/// [`crate::Signal::volume_ratio`].
+///
+/// Signals that cannot be checked, because a candle in the window does not provide
+/// [`Candle::volume`], there are fewer than `period` candles before the pattern, or the
+/// average volume is zero, are kept with [`crate::Signal::volume_ratio`] returning
+/// `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VolumeFilter {
+    /// The multiple of the average volume the pattern's volume must exceed.
+    pub multiple: f64,
+    /// The number of candles the average volume is calculated over.
+    pub period: usize,
+    /// What to do with a signal whose volume is not above the threshold.
+    pub action: FilterAction,
+}
+
+impl VolumeFilter {
+    /// Checks the volume of the candle at `index` against the preceding candles.
+    ///
+    /// Returns `None` if the volume cannot be checked.
+    pub(crate) fn check(&self, candles: &[SimpleCandle], index: usize) -> Option<RuleCheck> {
+        if self.period == 0 || index < self.period || index >= candles.len() {
+            return None;
+        }
+
+        let volume = candles[index].volume()?;
+        let average = candles[index - self.period..index]
+            .iter()
+            .map(|candle| candle.volume())
+            .sum::<Option<f64>>()?
+            / self.period as f64;
+        if average <= 0.0 {
+            return None;
+        }
+
+        let threshold = self.multiple * average;
+
+        Some(RuleCheck {
+            rule: "volume above average",
+            value: volume,
+            threshold,
+            average: Some(average),
+            comparison: Comparison::Greater,
+            passed: volume > threshold,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let filter = VolumeFilter {
+            multiple: 1.5,
+            period: 3,
+            action: FilterAction::Suppress,
+        };
+        let candles: Vec<_> = [100.0, 200.0, 300.0, 400.0]
+            .into_iter()
+            .map(|volume| {
+                SimpleCandle::try_new(1.0, 1.0, 1.0, 1.0)
+                    .unwrap()
+                    .try_with_volume(volume)
+                    .unwrap()
+            })
+            .collect();
+
+        let check = filter.check(&candles, 3).unwrap();
+        assert_eq!(check.average, Some(200.0));
+        assert_eq!(check.threshold, 300.0);
+        assert!(check.passed);
+
+        assert!(filter.check(&candles, 2).is_none());
+
+        let mut without_volume = candles.clone();
+        without_volume[1] = SimpleCandle::try_new(1.0, 1.0, 1.0, 1.0).unwrap();
+        assert!(filter.check(&without_volume, 3).is_none());
+    }
+}
@@ -1,9 +1,34 @@
-use super::super::api::{Error, Pattern, Signal, SimpleCandle};
+use super::super::api::{Error, Explanation, Pattern, Signal, SimpleCandle};
+use crate::{TrendFilter, VolumeFilter};
+
+/// The filters applied to the signals of a [`crate::Cdl`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Filters {
+    pub trend: Option<TrendFilter>,
+    pub volume: Option<VolumeFilter>,
+}
 
 pub trait CdlApiInternal {
+    /// Returns the signals and the number of leading candles the engine does not
+    /// evaluate.
     fn pattern(
         &self,
         pattern: Pattern,
         candles: &[SimpleCandle],
-    ) -> Result<Vec<Option<Signal>>, Error>;
+        filters: &Filters,
+    ) -> Result<(Vec<Option<Signal>>, usize), Error>;
+
+    /// The filters configured in the settings of the engine.
+    fn filters(&self) -> Filters;
+
+    /// The number of candles a signal at the last candle depends on.
+    fn history(&self, filters: &Filters) -> usize;
+
+    fn explain(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+        index: usize,
+        filters: &Filters,
+    ) -> Result<Explanation, Error>;
 }
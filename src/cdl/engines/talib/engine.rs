@@ -1,10 +1,14 @@
-use crate::cdl::engines::internal::CdlApiInternal;
+use crate::cdl::engines::internal::{CdlApiInternal, Filters};
 use crate::cdl::engines::talib::functions::TaCdlFnPtr;
-use crate::Error::{AlreadyConfigured, CalculationError};
-use crate::{Candle, Error, Pattern, Settings, Signal, SimpleCandle};
+use crate::cdl::engines::talib::rules;
+use crate::Error::{AlreadyConfigured, CalculationError, InvalidIndex};
+use crate::{
+    Candle, Error, Explanation, FilterAction, Pattern, Quality, RuleCheck, Settings, Signal,
+    SimpleCandle, Span,
+};
 use std::sync::OnceLock;
 use ta_lib_sys::{SetCandleSettings, RetCode};
-use crate::cdl::engines::talib::functions::{PIERCING_PENETRATION, STAR_PENETRATION};
+use crate::cdl::engines::talib::functions::initialize_penetration;
 use ta_lib_sys::CandleSettingType::*;
 use ta_lib_sys::RangeType::*;
 
@@ -50,14 +54,23 @@ pub fn configure(settings: Settings) -> Result<(), Error> {
 pub(crate) fn instance() -> &'static impl CdlApiInternal {
     static ENGINE: OnceLock<TaLibEngine> = OnceLock::new();
 
-    ENGINE.get_or_init(|| TaLibEngine::new(SETTINGS.get().cloned().unwrap_or_default()))
+    ENGINE.get_or_init(|| {
+        let settings = SETTINGS.get().cloned().unwrap_or_default();
+        // The only place the global TA-Lib state is written.
+        TaLibEngine::apply_settings(&settings);
+        TaLibEngine::new(settings)
+    })
 }
 
-struct TaLibEngine {}
+struct TaLibEngine {
+    settings: Settings,
+}
 impl TaLibEngine {
+    /// Creates an engine with `settings` for the rules and filters evaluated in Rust.
+    ///
+    /// The candle settings of TA-Lib are global and are applied only by [`instance`].
     fn new(settings: Settings) -> Self {
-        Self::apply_settings(&settings);
-        Self {}
+        Self { settings }
     }
 
     fn apply_settings(settings: &Settings) {
@@ -132,10 +145,9 @@ impl TaLibEngine {
                 settings.period,
                 settings.equal_factor,
             );
-
-            STAR_PENETRATION = settings.star_penetration_factor;
-            PIERCING_PENETRATION = settings.piercing_penetration_factor;
         }
+
+        initialize_penetration(settings);
     }
 }
 
@@ -144,8 +156,94 @@ impl CdlApiInternal for TaLibEngine {
         &self,
         pattern: Pattern,
         candles: &[SimpleCandle],
-    ) -> Result<Vec<Option<Signal>>, Error> {
-        Self::unsafe_call(candles, pattern.ta_lib_function())
+        filters: &Filters,
+    ) -> Result<(Vec<Option<Signal>>, usize), Error> {
+        let (scores, warm_up) = Self::unsafe_call(candles, pattern.ta_lib_function())?;
+
+        let signals = scores
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| {
+                if score == 0 {
+                    return None;
+                }
+
+                let signal = Signal::try_new(
+                    self.quality(pattern, candles, i),
+                    Span::ending_at(i, pattern.candle_count()),
+                )?;
+
+                let signal = Self::filter_trend(filters, pattern, candles, signal)?;
+
+                Self::filter_volume(filters, candles, signal)
+            })
+            .collect();
+
+        Ok((signals, warm_up))
+    }
+
+    fn filters(&self) -> Filters {
+        Filters {
+            trend: self.settings.trend_filter,
+            volume: self.settings.volume_filter,
+        }
+    }
+
+    fn history(&self, filters: &Filters) -> usize {
+        // Patterns span at most three candles and average `period` candles before the
+        // first of them. The trend filter looks back `window + 1` candles from the first
+        // candle, the volume filter `period` candles from the last one.
+        let pattern = self.settings.period.max(0) as usize + 3;
+        let trend = filters.trend.map_or(0, |filter| filter.window + 1 + 3);
+        let volume = filters.volume.map_or(0, |filter| filter.period);
+
+        pattern.max(trend).max(volume) + 1
+    }
+
+    fn explain(
+        &self,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+        index: usize,
+        filters: &Filters,
+    ) -> Result<Explanation, Error> {
+        let criteria =
+            rules::criteria(pattern, candles, index, &self.settings).ok_or_else(|| {
+                InvalidIndex(format!(
+                    "{pattern} cannot be evaluated at candle #{index} of {}",
+                    candles.len()
+                ))
+            })?;
+
+        let (signals, warm_up) = self.pattern(pattern, &candles[..=index], filters)?;
+        // TA-Lib does not evaluate the candles it needs to average the first one.
+        if index < warm_up {
+            return Err(InvalidIndex(format!(
+                "{pattern} is not evaluated before candle #{warm_up}, got #{index}"
+            )));
+        }
+
+        let detected = signals[index].is_some();
+
+        let mut rules: Vec<_> = criteria.iter().map(|criterion| criterion.check()).collect();
+        rules.extend(Self::check_trend(
+            filters,
+            pattern,
+            candles,
+            Span::ending_at(index, pattern.candle_count()),
+        ));
+        rules.extend(
+            filters
+                .volume
+                .and_then(|filter| filter.check(candles, index)),
+        );
+
+        Ok(Explanation {
+            pattern,
+            index,
+            detected,
+            rules,
+        })
     }
 }
 
@@ -156,29 +254,90 @@ trait IntoRows {
     fn highs(&self) -> Vec<f64>;
 }
 
-impl<C: Candle> IntoRows for [C] {
+impl<C: Candle<Price = f64>> IntoRows for [C] {
     fn opens(&self) -> Vec<f64> {
-        self.iter().map(|x| x.open().into()).collect()
+        self.iter().map(|x| x.open()).collect()
     }
 
     fn closes(&self) -> Vec<f64> {
-        self.iter().map(|x| x.close().into()).collect()
+        self.iter().map(|x| x.close()).collect()
     }
 
     fn lows(&self) -> Vec<f64> {
-        self.iter().map(|x| x.low().into()).collect()
+        self.iter().map(|x| x.low()).collect()
     }
 
     fn highs(&self) -> Vec<f64> {
-        self.iter().map(|x| x.high().into()).collect()
+        self.iter().map(|x| x.high()).collect()
     }
 }
 
 impl TaLibEngine {
-    fn unsafe_call<C: Candle>(
+    /// Grades a detection by how far it exceeds the thresholds of its rules.
+    fn quality(&self, pattern: Pattern, candles: &[SimpleCandle], index: usize) -> u8 {
+        rules::criteria(pattern, candles, index, &self.settings)
+            .map(|criteria| rules::quality(&criteria))
+            .unwrap_or(Quality::MAX)
+    }
+
+    /// Applies the [`crate::TrendFilter`] of `filters` to a signal of a reversal pattern.
+    fn filter_trend(
+        filters: &Filters,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+        signal: Signal,
+    ) -> Option<Signal> {
+        let Some(check) = Self::check_trend(filters, pattern, candles, signal.span()) else {
+            return Some(signal);
+        };
+
+        match filters.trend.map(|filter| filter.action) {
+            Some(FilterAction::Suppress) if !check.passed => None,
+            _ => Some(signal.with_trend_confirmed(check.passed)),
+        }
+    }
+
+    /// Applies the [`crate::VolumeFilter`] of `filters` to a signal.
+    fn filter_volume(
+        filters: &Filters,
+        candles: &[SimpleCandle],
+        signal: Signal,
+    ) -> Option<Signal> {
+        let Some(filter) = filters.volume else {
+            return Some(signal);
+        };
+        let Some(check) = filter.check(candles, signal.span().end()) else {
+            return Some(signal);
+        };
+
+        match filter.action {
+            FilterAction::Suppress if !check.passed => None,
+            _ => Some(signal.with_volume_ratio(check.value / check.average?)),
+        }
+    }
+
+    fn check_trend(
+        filters: &Filters,
+        pattern: Pattern,
+        candles: &[SimpleCandle],
+        span: Span,
+    ) -> Option<RuleCheck> {
+        let filter = filters.trend?;
+        let expected = pattern.info().prior_trend?;
+
+        filter.check(candles, span.start, expected)
+    }
+
+    /// Returns the scores of TA-Lib and the number of leading candles it does not
+    /// evaluate.
+    fn unsafe_call<C: Candle<Price = f64>>(
         candles: &[C],
         cdl_fn_ptr: TaCdlFnPtr,
-    ) -> Result<Vec<Option<Signal>>, Error> {
+    ) -> Result<(Vec<u8>, usize), Error> {
+        if candles.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
+
         let mut out_beg_idx: i32 = 0;
         let mut out_nb_element: i32 = 0;
         let mut out_arr: Vec<i32> = vec![0; candles.len()];
@@ -197,7 +356,13 @@ impl TaLibEngine {
             ))?;
         }
 
-        Self::map_ok(out_beg_idx, out_nb_element, out_arr)
+        let warm_up = if out_nb_element > 0 {
+            out_beg_idx as usize
+        } else {
+            candles.len()
+        };
+
+        Ok((Self::map_ok(out_beg_idx, out_nb_element, out_arr)?, warm_up))
     }
 
     fn map_error(res: RetCode) -> Result<(), Error> {
@@ -207,11 +372,7 @@ impl TaLibEngine {
         }
     }
 
-    fn map_ok(
-        out_beg_idx: i32,
-        out_nb_element: i32,
-        out_arr: Vec<i32>,
-    ) -> Result<Vec<Option<Signal>>, Error> {
+    fn map_ok(out_beg_idx: i32, out_nb_element: i32, out_arr: Vec<i32>) -> Result<Vec<u8>, Error> {
         let mut results: Vec<i32> = vec![0; out_arr.len()];
 
         let calculated_part = &out_arr[0..out_nb_element as usize];
@@ -225,10 +386,7 @@ impl TaLibEngine {
             }
         }
 
-        Ok(results
-            .iter()
-            .map(|&x| Signal::try_from_quality(x as u8))
-            .collect())
+        Ok(results.iter().map(|&x| x as u8).collect())
     }
 }
 
@@ -236,6 +394,7 @@ impl TaLibEngine {
 mod tests {
     use super::Pattern::*;
     use super::*;
+    use crate::{TrendFilter, TrendMethod, VolumeFilter};
 
     #[test]
     fn test_cdl_doji_t() {
@@ -455,6 +614,248 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_signal_span_covers_pattern_candles() {
+        let data: Vec<(f64, f64, f64, f64)> = vec![
+            (0.6144, 0.624, 0.6018, 0.6169),
+            (0.6198, 0.6383, 0.6094, 0.633),
+            (0.6335, 0.6478, 0.616, 0.6277),
+            (0.6275, 0.6446, 0.618, 0.6428),
+            (0.6429, 0.6445, 0.635, 0.6421),
+            (0.6431, 0.6621, 0.6056, 0.6344),
+            (0.6345, 0.649, 0.6255, 0.6429),
+            (0.6434, 0.6469, 0.62, 0.6302), // Bearish Engulfing
+        ];
+        let candles: Vec<SimpleCandle> = data
+            .into_iter()
+            .map(|(open, high, low, close)| SimpleCandle::try_new(open, close, high, low).unwrap())
+            .collect();
+
+        let signals = crate::cdl().pattern(BearishEngulfing, &candles).unwrap();
+        let span = signals[7]
+            .expect("Bearish Engulfing should be found")
+            .span();
+
+        assert_eq!(span, Span { start: 6, len: 2 });
+        assert_eq!(span.end(), 7);
+        assert_eq!(BearishEngulfing.candle_count(), 2);
+    }
+
+    #[test]
+    fn test_rules_agree_with_ta_lib() {
+        let candles = pseudo_random_candles(2000);
+        let engine = engine(Settings::default());
+
+        for &pattern in Pattern::all() {
+            let (signals, _) = engine.pattern(pattern, &candles, &engine.filters()).unwrap();
+
+            // TA-Lib does not evaluate the candles within the lookback period.
+            for (i, signal) in signals.iter().enumerate().skip(20) {
+                let criteria = rules::criteria(pattern, &candles, i, &engine.settings).unwrap();
+                let passed = criteria.iter().all(|c| c.passed());
+
+                assert_eq!(signal.is_some(), passed, "{pattern:?} at {i}: {criteria:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_textbook_hammer_scores_higher_than_borderline() {
+        let textbook = hammer_series((10.2, 10.2, 7.0, 10.0));
+        let borderline = hammer_series((10.3, 10.3, 8.9, 10.0));
+
+        let textbook_signal = crate::cdl().pattern(Hammer, &textbook).unwrap()[11]
+            .expect("textbook Hammer should be found");
+        let borderline_signal = crate::cdl().pattern(Hammer, &borderline).unwrap()[11]
+            .expect("borderline Hammer should be found");
+
+        assert!(textbook_signal.quality > borderline_signal.quality);
+        assert!(textbook_signal.quality.value() > 50);
+        assert!(borderline_signal.quality.value() < 50);
+    }
+
+    #[test]
+    fn test_explain() {
+        let hammer = hammer_series((10.2, 10.2, 7.0, 10.0));
+        let not_hammer = hammer_series((10.2, 10.2, 9.8, 10.0));
+
+        let explanation = crate::cdl().explain(Hammer, &hammer, 11).unwrap();
+        assert!(explanation.detected);
+        assert!(explanation.rules.iter().all(|rule| rule.passed));
+
+        let explanation = crate::cdl().explain(Hammer, &not_hammer, 11).unwrap();
+        assert!(!explanation.detected);
+        let failed: Vec<_> = explanation
+            .rules
+            .iter()
+            .filter(|rule| !rule.passed)
+            .map(|rule| rule.rule)
+            .collect();
+        assert_eq!(failed, vec!["lower shadow long"]);
+
+        let lower_shadow = &explanation.rules[1];
+        assert_eq!(lower_shadow.average, Some(1.0));
+        assert_eq!(lower_shadow.threshold, 1.0);
+
+        assert!(crate::cdl().explain(Hammer, &hammer, 12).is_err());
+        assert!(crate::cdl().explain(MorningStar, &hammer, 1).is_err());
+        // Within the warm-up the rules can pass although TA-Lib reports nothing.
+        assert!(crate::cdl().explain(Hammer, &hammer, 5).is_err());
+    }
+
+    #[test]
+    fn test_trend_filter() {
+        let filter = |action| TrendFilter {
+            method: TrendMethod::SmaSlope,
+            window: 10,
+            action,
+        };
+        let annotating = crate::cdl().with_trend_filter(filter(FilterAction::Annotate));
+        let suppressing = crate::cdl().with_trend_filter(filter(FilterAction::Suppress));
+
+        let after_decline = hammer_series((10.2, 10.2, 7.0, 10.0));
+        let mut after_rally: Vec<_> = (0..11)
+            .map(|i| {
+                let open = i as f64;
+                SimpleCandle::try_new(open, open + 1.0, open + 1.2, open - 0.2).unwrap()
+            })
+            .collect();
+        after_rally.push(SimpleCandle::try_new(10.2, 10.0, 10.2, 7.0).unwrap());
+
+        let signal = annotating.pattern(Hammer, &after_decline).unwrap()[11].unwrap();
+        assert_eq!(signal.trend_confirmed(), Some(true));
+        assert!(suppressing.pattern(Hammer, &after_decline).unwrap()[11].is_some());
+
+        let signal = annotating.pattern(Hammer, &after_rally).unwrap()[11].unwrap();
+        assert_eq!(signal.trend_confirmed(), Some(false));
+        assert!(suppressing.pattern(Hammer, &after_rally).unwrap()[11].is_none());
+
+        let signal = crate::cdl().pattern(Hammer, &after_rally).unwrap()[11].unwrap();
+        assert_eq!(signal.trend_confirmed(), None);
+
+        let explanation = annotating.explain(Hammer, &after_rally, 11).unwrap();
+        let trend = explanation.rules.last().unwrap();
+        assert_eq!(trend.rule, "prior trend down");
+        assert!(!trend.passed);
+    }
+
+    #[test]
+    fn test_volume_filter() {
+        let filter = |action| VolumeFilter {
+            multiple: 1.5,
+            period: 5,
+            action,
+        };
+        let annotating = crate::cdl().with_volume_filter(filter(FilterAction::Annotate));
+        let suppressing = crate::cdl().with_volume_filter(filter(FilterAction::Suppress));
+
+        let with_volume = |hammer_volume| {
+            let mut candles = hammer_series((10.2, 10.2, 7.0, 10.0));
+            let last = candles.len() - 1;
+            for (i, candle) in candles.iter_mut().enumerate() {
+                let volume = if i == last { hammer_volume } else { 1000.0 };
+                *candle = candle.clone().try_with_volume(volume).unwrap();
+            }
+            candles
+        };
+
+        let heavy = with_volume(2000.0);
+        let signal = annotating.pattern(Hammer, &heavy).unwrap()[11].unwrap();
+        assert_eq!(signal.volume_ratio(), Some(2.0));
+        assert!(suppressing.pattern(Hammer, &heavy).unwrap()[11].is_some());
+
+        let light = with_volume(1200.0);
+        let signal = annotating.pattern(Hammer, &light).unwrap()[11].unwrap();
+        assert_eq!(signal.volume_ratio(), Some(1.2));
+        assert!(suppressing.pattern(Hammer, &light).unwrap()[11].is_none());
+
+        let without_volume = hammer_series((10.2, 10.2, 7.0, 10.0));
+        let signal = suppressing.pattern(Hammer, &without_volume).unwrap()[11].unwrap();
+        assert_eq!(signal.volume_ratio(), None);
+    }
+
+    #[test]
+    fn test_dark_cloud_cover_uses_piercing_penetration() {
+        // Small candles, a long white candle of body 10, then a black candle opening
+        // above its high and closing at `close`.
+        let series = |close: f64| -> Vec<SimpleCandle> {
+            let mut candles: Vec<_> = (0..11)
+                .map(|_| SimpleCandle::try_new(100.0, 101.0, 101.5, 99.5).unwrap())
+                .collect();
+            candles.push(SimpleCandle::try_new(100.0, 110.0, 111.0, 99.0).unwrap());
+            candles.push(SimpleCandle::try_new(112.0, close, 112.5, close - 0.5).unwrap());
+            candles
+        };
+
+        // Penetrating 40% of the body was detected with the star penetration of 0.3,
+        // but not with the piercing penetration of 0.5.
+        let shallow = crate::cdl().pattern(DarkCloudCover, &series(106.0)).unwrap();
+        assert!(shallow[12].is_none());
+
+        let deep = crate::cdl().pattern(DarkCloudCover, &series(104.0)).unwrap();
+        assert!(deep[12].is_some());
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let engine = instance();
+        let warm_up = |pattern, candles: &[SimpleCandle]| {
+            engine.pattern(pattern, candles, &engine.filters()).unwrap().1
+        };
+        let candles = hammer_series((10.2, 10.2, 7.0, 10.0));
+
+        assert_eq!(warm_up(Hammer, &candles), 11);
+        assert_eq!(warm_up(Doji, &candles), 10);
+        assert_eq!(warm_up(MorningStar, &candles[..5]), 5);
+        assert_eq!(warm_up(Doji, &[]), 0);
+    }
+
+    /// Ten declining candles with a real body of 1.0 followed by the given hammer candle.
+    fn hammer_series(hammer: (f64, f64, f64, f64)) -> Vec<SimpleCandle> {
+        let mut data: Vec<(f64, f64, f64, f64)> = (0..11)
+            .map(|i| {
+                let open = 21.0 - i as f64;
+                (open, open + 0.2, open - 1.2, open - 1.0)
+            })
+            .collect();
+        data.push(hammer);
+
+        data.into_iter()
+            .map(|(open, high, low, close)| SimpleCandle::try_new(open, close, high, low).unwrap())
+            .collect()
+    }
+
+    /// A deterministic random walk of candles with alternating trends.
+    fn pseudo_random_candles(len: usize) -> Vec<SimpleCandle> {
+        let mut seed: u64 = 42;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let mut close = 100.0;
+        (0..len)
+            .map(|i| {
+                let drift = [0.0, 2.5, 0.0, -2.5][i / 25 % 4];
+                let open: f64 = close + (next() - 0.5) * 4.0;
+                close = open + drift + (next() - 0.5) * 6.0;
+                let mut shadow = || (next() - 0.3).max(0.0) * 3.0;
+                let high = open.max(close) + shadow();
+                let low = open.min(close) - shadow();
+                SimpleCandle::try_new(open, close, high, low).unwrap()
+            })
+            .collect()
+    }
+
+    /// Creates an engine for `settings` on top of the TA-Lib state applied by the shared
+    /// instance.
+    fn engine(settings: Settings) -> TaLibEngine {
+        instance();
+        TaLibEngine::new(settings)
+    }
+
     fn helper<F>(data: Vec<(f64, f64, f64, f64)>, expected: Vec<u8>, indicator_fn: F)
     where
         F: Fn(&[SimpleCandle]) -> Result<Vec<Option<Signal>>, Error>,
@@ -463,13 +864,11 @@ mod tests {
             .into_iter()
             .map(|(open, high, low, close)| SimpleCandle::try_new(open, close, high, low).unwrap())
             .collect();
-        let expected_signals: Vec<Option<Signal>> = expected
-            .into_iter()
-            .map(|x| Signal::try_from_quality(x))
-            .collect();
+        let expected_detections: Vec<bool> = expected.into_iter().map(|x| x > 0).collect();
         let result = indicator_fn(candles.as_slice())
             .expect("Функция индикатора должна вернуть Ok в тестах");
+        let detections: Vec<bool> = result.iter().map(Option::is_some).collect();
 
-        assert_eq!(result, expected_signals);
+        assert_eq!(detections, expected_detections);
     }
 }
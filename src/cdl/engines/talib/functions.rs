@@ -5,18 +5,23 @@ use std::sync::Once;
 use ta_lib_sys::{CDLKICKING, CDL3BLACKCROWS, CDL3WHITESOLDIERS, CDLDARKCLOUDCOVER, CDLDOJI, CDLDRAGONFLYDOJI, CDLENGULFING, CDLEVENINGSTAR, CDLGRAVESTONEDOJI, CDLHAMMER, CDLHANGINGMAN, CDLHARAMI, CDLHARAMICROSS, CDLINVERTEDHAMMER, CDLLONGLINE, CDLMARUBOZU, CDLMORNINGSTAR, CDLPIERCING, CDLSHOOTINGSTAR, CDLSHORTLINE, CDLSPINNINGTOP};
 use ta_lib_sys::RetCode;
 
-pub(crate) static mut STAR_PENETRATION: f64 = 0.0;
-pub(crate) static mut PIERCING_PENETRATION: f64 = 0.0;
+static mut STAR_PENETRATION: f64 = 0.0;
+static mut PIERCING_PENETRATION: f64 = 0.0;
 
 static INIT_PENETRATION: Once = Once::new();
 
-fn initialize_penetration_defaults() {
+/// Sets the penetration factors once; later calls do not change them.
+pub(crate) fn initialize_penetration(settings: &Settings) {
     INIT_PENETRATION.call_once(|| unsafe {
-        STAR_PENETRATION = Settings::default().star_penetration_factor;
-        PIERCING_PENETRATION = Settings::default().piercing_penetration_factor;
+        STAR_PENETRATION = settings.star_penetration_factor;
+        PIERCING_PENETRATION = settings.piercing_penetration_factor;
     });
 }
 
+fn initialize_penetration_defaults() {
+    initialize_penetration(&Settings::default());
+}
+
 #[allow(non_snake_case)]
 unsafe extern "C" fn ta_cdlmorningstar(
     startIdx: i32,
@@ -86,7 +91,7 @@ unsafe extern "C" fn ta_cdldarkcloudcover(
     outInteger: *mut i32,
 ) -> RetCode {
     initialize_penetration_defaults();
-    let penetration = STAR_PENETRATION;
+    let penetration = PIERCING_PENETRATION;
     CDLDARKCLOUDCOVER(
         startIdx,
         endIdx,
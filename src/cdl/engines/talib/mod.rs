@@ -1,3 +1,4 @@
 pub(crate) mod engine;
 
 mod functions;
+mod rules;
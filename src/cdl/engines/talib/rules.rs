@@ -0,0 +1,941 @@
+//! Native evaluation of the TA-Lib recognition rules.
+//!
+//! TA-Lib only reports whether a pattern was found. The functions in this module
+//! re-evaluate each rule of a pattern with the same rolling averages and `Settings`
+//! factors as the engine, which makes it possible to measure how clearly a
+//! detection satisfies its rules.
+
+use crate::{Candle, Comparison, Pattern, RuleCheck, Settings, SimpleCandle};
+use Pattern::*;
+
+/// The candle settings configured by `TaLibEngine::apply_settings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CandleSetting {
+    BodyLong,
+    BodyShort,
+    BodyDoji,
+    ShadowLong,
+    ShadowShort,
+    ShadowVeryShort,
+    Near,
+    Far,
+}
+
+impl CandleSetting {
+    fn factor(&self, settings: &Settings) -> f64 {
+        match self {
+            CandleSetting::BodyLong => settings.body_long_factor,
+            CandleSetting::BodyShort => settings.body_short_factor,
+            CandleSetting::BodyDoji => settings.body_doji_factor,
+            CandleSetting::ShadowLong => settings.shadow_long_factor,
+            CandleSetting::ShadowShort => settings.shadow_short_factor,
+            CandleSetting::ShadowVeryShort => settings.shadow_very_short_factor,
+            CandleSetting::Near => settings.near_factor,
+            CandleSetting::Far => settings.far_factor,
+        }
+    }
+
+    fn uses_high_low(&self) -> bool {
+        matches!(
+            self,
+            CandleSetting::BodyDoji | CandleSetting::ShadowShort | CandleSetting::ShadowVeryShort
+        )
+    }
+}
+
+/// A single rule of a pattern evaluated at a specific candle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Criterion {
+    pub(crate) rule: &'static str,
+    pub(crate) value: f64,
+    pub(crate) threshold: f64,
+    pub(crate) average: Option<f64>,
+    pub(crate) comparison: Comparison,
+    scale: Option<f64>,
+}
+
+impl Criterion {
+    pub(crate) fn passed(&self) -> bool {
+        self.comparison.holds(self.value, self.threshold)
+    }
+
+    pub(crate) fn check(&self) -> RuleCheck {
+        RuleCheck {
+            rule: self.rule,
+            value: self.value,
+            threshold: self.threshold,
+            average: self.average,
+            comparison: self.comparison,
+            passed: self.passed(),
+        }
+    }
+
+    /// Returns how far the value exceeds the threshold, from 0 to 1,
+    /// or `None` for rules without a measurable margin (e.g. candle colors).
+    fn margin(&self) -> Option<f64> {
+        let scale = self.scale?;
+
+        if !self.passed() {
+            return Some(0.0);
+        }
+        if scale <= 0.0 {
+            return Some(1.0);
+        }
+
+        let excess = match self.comparison {
+            Comparison::Less | Comparison::LessOrEqual => self.threshold - self.value,
+            Comparison::Greater | Comparison::GreaterOrEqual => self.value - self.threshold,
+        };
+
+        Some((excess / scale).clamp(0.0, 1.0))
+    }
+}
+
+/// Computes a quality score from 1 to 100 for a detection.
+///
+/// The score is the average margin by which the measurable rules are satisfied.
+pub(crate) fn quality(criteria: &[Criterion]) -> u8 {
+    let margins: Vec<f64> = criteria.iter().filter_map(Criterion::margin).collect();
+
+    if margins.is_empty() {
+        return 100;
+    }
+
+    let mean = margins.iter().sum::<f64>() / margins.len() as f64;
+
+    (1.0 + 99.0 * mean).round() as u8
+}
+
+/// Evaluates the rules of `pattern` for the candle with index `index`.
+///
+/// Returns `None` if `index` is out of bounds or there are not enough candles before it
+/// to evaluate the pattern. When fewer than `Settings::period` candles precede a rule's
+/// candle, its rolling average is computed over the available candles.
+pub(crate) fn criteria(
+    pattern: Pattern,
+    candles: &[SimpleCandle],
+    index: usize,
+    settings: &Settings,
+) -> Option<Vec<Criterion>> {
+    let bars = Bars { candles, settings };
+
+    if index >= candles.len() || index < prior_candles(pattern) {
+        return None;
+    }
+
+    let i = index;
+    let criteria = match pattern {
+        Hammer => vec![
+            bars.body_short(i),
+            bars.above_average(
+                "lower shadow long",
+                bars.lower_shadow(i),
+                CandleSetting::ShadowLong,
+                i,
+            ),
+            bars.upper_shadow_very_short(i),
+            bars.price(
+                "real body near the prior low",
+                bars.body_bottom(i),
+                bars.low(i - 1) + bars.threshold(CandleSetting::Near, i - 1),
+                Comparison::LessOrEqual,
+                i,
+            ),
+        ],
+        HangingMan => vec![
+            bars.body_short(i),
+            bars.above_average(
+                "lower shadow long",
+                bars.lower_shadow(i),
+                CandleSetting::ShadowLong,
+                i,
+            ),
+            bars.upper_shadow_very_short(i),
+            bars.price(
+                "real body near the prior high",
+                bars.body_bottom(i),
+                bars.high(i - 1) - bars.threshold(CandleSetting::Near, i - 1),
+                Comparison::GreaterOrEqual,
+                i,
+            ),
+        ],
+        InvertedHammer => vec![
+            bars.body_short(i),
+            bars.above_average(
+                "upper shadow long",
+                bars.upper_shadow(i),
+                CandleSetting::ShadowLong,
+                i,
+            ),
+            bars.lower_shadow_very_short(i),
+            bars.price(
+                "real body gaps down",
+                bars.body_top(i),
+                bars.body_bottom(i - 1),
+                Comparison::Less,
+                i,
+            ),
+        ],
+        ShootingStar => vec![
+            bars.body_short(i),
+            bars.above_average(
+                "upper shadow long",
+                bars.upper_shadow(i),
+                CandleSetting::ShadowLong,
+                i,
+            ),
+            bars.lower_shadow_very_short(i),
+            bars.price(
+                "real body gaps up",
+                bars.body_bottom(i),
+                bars.body_top(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+        ],
+        Doji => vec![bars.body_doji(i)],
+        DragonFly => vec![
+            bars.body_doji(i),
+            bars.upper_shadow_very_short(i),
+            bars.above_average(
+                "lower shadow longer than very short",
+                bars.lower_shadow(i),
+                CandleSetting::ShadowVeryShort,
+                i,
+            ),
+        ],
+        Gravestone => vec![
+            bars.body_doji(i),
+            bars.lower_shadow_very_short(i),
+            bars.above_average(
+                "upper shadow longer than very short",
+                bars.upper_shadow(i),
+                CandleSetting::ShadowVeryShort,
+                i,
+            ),
+        ],
+        SpinningTop => vec![
+            bars.body_short(i),
+            bars.price(
+                "upper shadow longer than real body",
+                bars.upper_shadow(i),
+                bars.real_body(i),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "lower shadow longer than real body",
+                bars.lower_shadow(i),
+                bars.real_body(i),
+                Comparison::Greater,
+                i,
+            ),
+        ],
+        BullishEngulfing => vec![
+            bars.color("1st: black", i - 1, false),
+            bars.color("2nd: white", i, true),
+            bars.price(
+                "2nd: close above 1st open",
+                bars.close(i),
+                bars.open(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: open below 1st close",
+                bars.open(i),
+                bars.close(i - 1),
+                Comparison::Less,
+                i,
+            ),
+        ],
+        BearishEngulfing => vec![
+            bars.color("1st: white", i - 1, true),
+            bars.color("2nd: black", i, false),
+            bars.price(
+                "2nd: open above 1st close",
+                bars.open(i),
+                bars.close(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: close below 1st open",
+                bars.close(i),
+                bars.open(i - 1),
+                Comparison::Less,
+                i,
+            ),
+        ],
+        BullishHarami | BearishHarami | BullishHaramiCross | BearishHaramiCross => {
+            let second = if matches!(pattern, BullishHarami | BearishHarami) {
+                bars.below_average(
+                    "2nd: real body short",
+                    bars.real_body(i),
+                    CandleSetting::BodyShort,
+                    i,
+                )
+            } else {
+                bars.body_doji(i)
+            };
+
+            vec![
+                bars.color(
+                    if matches!(pattern, BullishHarami | BullishHaramiCross) {
+                        "1st: black"
+                    } else {
+                        "1st: white"
+                    },
+                    i - 1,
+                    matches!(pattern, BearishHarami | BearishHaramiCross),
+                ),
+                bars.above_average(
+                    "1st: real body long",
+                    bars.real_body(i - 1),
+                    CandleSetting::BodyLong,
+                    i - 1,
+                ),
+                second,
+                bars.price(
+                    "2nd: real body top inside 1st real body",
+                    bars.body_top(i),
+                    bars.body_top(i - 1),
+                    Comparison::Less,
+                    i,
+                ),
+                bars.price(
+                    "2nd: real body bottom inside 1st real body",
+                    bars.body_bottom(i),
+                    bars.body_bottom(i - 1),
+                    Comparison::Greater,
+                    i,
+                ),
+            ]
+        }
+        BullishMarubozu | BearishMarubozu => vec![
+            bars.direction(i, pattern == BullishMarubozu),
+            bars.body_long(i),
+            bars.upper_shadow_very_short(i),
+            bars.lower_shadow_very_short(i),
+        ],
+        BullishLongLine | BearishLongLine => vec![
+            bars.direction(i, pattern == BullishLongLine),
+            bars.body_long(i),
+            bars.upper_shadow_short(i),
+            bars.lower_shadow_short(i),
+        ],
+        BullishShortLine | BearishShortLine => vec![
+            bars.direction(i, pattern == BullishShortLine),
+            bars.body_short(i),
+            bars.upper_shadow_short(i),
+            bars.lower_shadow_short(i),
+        ],
+        BullishKicking | BearishKicking => {
+            let bullish = pattern == BullishKicking;
+            let gap = if bullish {
+                bars.price(
+                    "2nd: gaps up",
+                    bars.low(i),
+                    bars.high(i - 1),
+                    Comparison::Greater,
+                    i,
+                )
+            } else {
+                bars.price(
+                    "2nd: gaps down",
+                    bars.high(i),
+                    bars.low(i - 1),
+                    Comparison::Less,
+                    i,
+                )
+            };
+
+            vec![
+                bars.color(
+                    if bullish { "1st: black" } else { "1st: white" },
+                    i - 1,
+                    !bullish,
+                ),
+                bars.above_average(
+                    "1st: real body long",
+                    bars.real_body(i - 1),
+                    CandleSetting::BodyLong,
+                    i - 1,
+                ),
+                bars.below_average(
+                    "1st: upper shadow very short",
+                    bars.upper_shadow(i - 1),
+                    CandleSetting::ShadowVeryShort,
+                    i - 1,
+                ),
+                bars.below_average(
+                    "1st: lower shadow very short",
+                    bars.lower_shadow(i - 1),
+                    CandleSetting::ShadowVeryShort,
+                    i - 1,
+                ),
+                bars.color(
+                    if bullish { "2nd: white" } else { "2nd: black" },
+                    i,
+                    bullish,
+                ),
+                bars.above_average(
+                    "2nd: real body long",
+                    bars.real_body(i),
+                    CandleSetting::BodyLong,
+                    i,
+                ),
+                bars.below_average(
+                    "2nd: upper shadow very short",
+                    bars.upper_shadow(i),
+                    CandleSetting::ShadowVeryShort,
+                    i,
+                ),
+                bars.below_average(
+                    "2nd: lower shadow very short",
+                    bars.lower_shadow(i),
+                    CandleSetting::ShadowVeryShort,
+                    i,
+                ),
+                gap,
+            ]
+        }
+        PiercingLine => vec![
+            bars.color("1st: black", i - 1, false),
+            bars.above_average(
+                "1st: real body long",
+                bars.real_body(i - 1),
+                CandleSetting::BodyLong,
+                i - 1,
+            ),
+            bars.color("2nd: white", i, true),
+            bars.above_average(
+                "2nd: real body long",
+                bars.real_body(i),
+                CandleSetting::BodyLong,
+                i,
+            ),
+            bars.price(
+                "2nd: open below 1st low",
+                bars.open(i),
+                bars.low(i - 1),
+                Comparison::Less,
+                i,
+            ),
+            bars.price(
+                "2nd: close within 1st real body",
+                bars.close(i),
+                bars.open(i - 1),
+                Comparison::Less,
+                i,
+            ),
+            // TA-Lib uses a fixed 50% penetration for this pattern.
+            bars.price(
+                "2nd: close above 1st real body midpoint",
+                bars.close(i),
+                bars.close(i - 1) + bars.real_body(i - 1) * 0.5,
+                Comparison::Greater,
+                i,
+            ),
+        ],
+        DarkCloudCover => vec![
+            bars.color("1st: white", i - 1, true),
+            bars.above_average(
+                "1st: real body long",
+                bars.real_body(i - 1),
+                CandleSetting::BodyLong,
+                i - 1,
+            ),
+            bars.color("2nd: black", i, false),
+            bars.price(
+                "2nd: open above 1st high",
+                bars.open(i),
+                bars.high(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: close within 1st real body",
+                bars.close(i),
+                bars.open(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: close penetrates 1st real body",
+                bars.close(i),
+                bars.close(i - 1) - bars.real_body(i - 1) * settings.piercing_penetration_factor,
+                Comparison::Less,
+                i,
+            ),
+        ],
+        MorningStar => vec![
+            bars.color("1st: black", i - 2, false),
+            bars.above_average(
+                "1st: real body long",
+                bars.real_body(i - 2),
+                CandleSetting::BodyLong,
+                i - 2,
+            ),
+            bars.below_average(
+                "2nd: real body short",
+                bars.real_body(i - 1),
+                CandleSetting::BodyShort,
+                i - 1,
+            ),
+            bars.price(
+                "2nd: real body gaps down",
+                bars.body_top(i - 1),
+                bars.body_bottom(i - 2),
+                Comparison::Less,
+                i,
+            ),
+            bars.color("3rd: white", i, true),
+            bars.above_average(
+                "3rd: real body longer than short",
+                bars.real_body(i),
+                CandleSetting::BodyShort,
+                i,
+            ),
+            bars.price(
+                "3rd: close penetrates 1st real body",
+                bars.close(i),
+                bars.close(i - 2) + bars.real_body(i - 2) * settings.star_penetration_factor,
+                Comparison::Greater,
+                i,
+            ),
+        ],
+        EveningStar => vec![
+            bars.color("1st: white", i - 2, true),
+            bars.above_average(
+                "1st: real body long",
+                bars.real_body(i - 2),
+                CandleSetting::BodyLong,
+                i - 2,
+            ),
+            bars.below_average(
+                "2nd: real body short",
+                bars.real_body(i - 1),
+                CandleSetting::BodyShort,
+                i - 1,
+            ),
+            bars.price(
+                "2nd: real body gaps up",
+                bars.body_bottom(i - 1),
+                bars.body_top(i - 2),
+                Comparison::Greater,
+                i,
+            ),
+            bars.color("3rd: black", i, false),
+            bars.above_average(
+                "3rd: real body longer than short",
+                bars.real_body(i),
+                CandleSetting::BodyShort,
+                i,
+            ),
+            bars.price(
+                "3rd: close penetrates 1st real body",
+                bars.close(i),
+                bars.close(i - 2) - bars.real_body(i - 2) * settings.star_penetration_factor,
+                Comparison::Less,
+                i,
+            ),
+        ],
+        ThreeWhiteSoldiers => vec![
+            bars.color("1st: white", i - 2, true),
+            bars.below_average(
+                "1st: upper shadow very short",
+                bars.upper_shadow(i - 2),
+                CandleSetting::ShadowVeryShort,
+                i - 2,
+            ),
+            bars.color("2nd: white", i - 1, true),
+            bars.below_average(
+                "2nd: upper shadow very short",
+                bars.upper_shadow(i - 1),
+                CandleSetting::ShadowVeryShort,
+                i - 1,
+            ),
+            bars.color("3rd: white", i, true),
+            bars.below_average(
+                "3rd: upper shadow very short",
+                bars.upper_shadow(i),
+                CandleSetting::ShadowVeryShort,
+                i,
+            ),
+            bars.price(
+                "2nd: close above 1st close",
+                bars.close(i - 1),
+                bars.close(i - 2),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "3rd: close above 2nd close",
+                bars.close(i),
+                bars.close(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: open above 1st open",
+                bars.open(i - 1),
+                bars.open(i - 2),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "2nd: open near 1st real body",
+                bars.open(i - 1),
+                bars.close(i - 2) + bars.threshold(CandleSetting::Near, i - 2),
+                Comparison::LessOrEqual,
+                i,
+            ),
+            bars.price(
+                "3rd: open above 2nd open",
+                bars.open(i),
+                bars.open(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "3rd: open near 2nd real body",
+                bars.open(i),
+                bars.close(i - 1) + bars.threshold(CandleSetting::Near, i - 1),
+                Comparison::LessOrEqual,
+                i,
+            ),
+            bars.price(
+                "2nd: real body not far shorter than 1st",
+                bars.real_body(i - 1),
+                bars.real_body(i - 2) - bars.threshold(CandleSetting::Far, i - 2),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "3rd: real body not far shorter than 2nd",
+                bars.real_body(i),
+                bars.real_body(i - 1) - bars.threshold(CandleSetting::Far, i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.above_average(
+                "3rd: real body longer than short",
+                bars.real_body(i),
+                CandleSetting::BodyShort,
+                i,
+            ),
+        ],
+        ThreeBlackCrows => vec![
+            bars.color("prior candle: white", i - 3, true),
+            bars.color("1st: black", i - 2, false),
+            bars.below_average(
+                "1st: lower shadow very short",
+                bars.lower_shadow(i - 2),
+                CandleSetting::ShadowVeryShort,
+                i - 2,
+            ),
+            bars.color("2nd: black", i - 1, false),
+            bars.below_average(
+                "2nd: lower shadow very short",
+                bars.lower_shadow(i - 1),
+                CandleSetting::ShadowVeryShort,
+                i - 1,
+            ),
+            bars.color("3rd: black", i, false),
+            bars.below_average(
+                "3rd: lower shadow very short",
+                bars.lower_shadow(i),
+                CandleSetting::ShadowVeryShort,
+                i,
+            ),
+            bars.price(
+                "2nd: open below 1st open",
+                bars.open(i - 1),
+                bars.open(i - 2),
+                Comparison::Less,
+                i,
+            ),
+            bars.price(
+                "2nd: open above 1st close",
+                bars.open(i - 1),
+                bars.close(i - 2),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "3rd: open below 2nd open",
+                bars.open(i),
+                bars.open(i - 1),
+                Comparison::Less,
+                i,
+            ),
+            bars.price(
+                "3rd: open above 2nd close",
+                bars.open(i),
+                bars.close(i - 1),
+                Comparison::Greater,
+                i,
+            ),
+            bars.price(
+                "1st: close below prior candle high",
+                bars.close(i - 2),
+                bars.high(i - 3),
+                Comparison::Less,
+                i,
+            ),
+            bars.price(
+                "2nd: close below 1st close",
+                bars.close(i - 1),
+                bars.close(i - 2),
+                Comparison::Less,
+                i,
+            ),
+            bars.price(
+                "3rd: close below 2nd close",
+                bars.close(i),
+                bars.close(i - 1),
+                Comparison::Less,
+                i,
+            ),
+        ],
+    };
+
+    Some(criteria)
+}
+
+/// The number of candles referenced by the rules before the last candle of the pattern.
+fn prior_candles(pattern: Pattern) -> usize {
+    match pattern {
+        Doji | DragonFly | Gravestone | SpinningTop | BullishMarubozu | BearishMarubozu
+        | BullishLongLine | BearishLongLine | BullishShortLine | BearishShortLine => 0,
+        ThreeWhiteSoldiers | MorningStar | EveningStar => 2,
+        ThreeBlackCrows => 3,
+        _ => 1,
+    }
+}
+
+struct Bars<'a> {
+    candles: &'a [SimpleCandle],
+    settings: &'a Settings,
+}
+
+impl Bars<'_> {
+    fn period(&self) -> usize {
+        self.settings.period.max(0) as usize
+    }
+
+    fn open(&self, i: usize) -> f64 {
+        self.candles[i].open()
+    }
+
+    fn close(&self, i: usize) -> f64 {
+        self.candles[i].close()
+    }
+
+    fn high(&self, i: usize) -> f64 {
+        self.candles[i].high()
+    }
+
+    fn low(&self, i: usize) -> f64 {
+        self.candles[i].low()
+    }
+
+    fn body_top(&self, i: usize) -> f64 {
+        self.open(i).max(self.close(i))
+    }
+
+    fn body_bottom(&self, i: usize) -> f64 {
+        self.open(i).min(self.close(i))
+    }
+
+    fn real_body(&self, i: usize) -> f64 {
+        (self.close(i) - self.open(i)).abs()
+    }
+
+    fn upper_shadow(&self, i: usize) -> f64 {
+        self.high(i) - self.body_top(i)
+    }
+
+    fn lower_shadow(&self, i: usize) -> f64 {
+        self.body_bottom(i) - self.low(i)
+    }
+
+    fn range(&self, setting: CandleSetting, i: usize) -> f64 {
+        if setting.uses_high_low() {
+            self.high(i) - self.low(i)
+        } else {
+            self.real_body(i)
+        }
+    }
+
+    /// The average range of the candles preceding `i`, as used by TA-Lib.
+    fn average(&self, setting: CandleSetting, i: usize) -> f64 {
+        let start = i.saturating_sub(self.period());
+
+        if start == i {
+            return self.range(setting, i);
+        }
+
+        (start..i).map(|j| self.range(setting, j)).sum::<f64>() / (i - start) as f64
+    }
+
+    fn threshold(&self, setting: CandleSetting, i: usize) -> f64 {
+        setting.factor(self.settings) * self.average(setting, i)
+    }
+
+    /// A criterion comparing `value` with the rolling average of `setting`.
+    fn candle_average(
+        &self,
+        rule: &'static str,
+        value: f64,
+        setting: CandleSetting,
+        comparison: Comparison,
+        i: usize,
+    ) -> Criterion {
+        let average = self.average(setting, i);
+        let threshold = setting.factor(self.settings) * average;
+
+        Criterion {
+            rule,
+            value,
+            threshold,
+            average: Some(average),
+            comparison,
+            scale: Some(threshold),
+        }
+    }
+
+    fn above_average(
+        &self,
+        rule: &'static str,
+        value: f64,
+        setting: CandleSetting,
+        i: usize,
+    ) -> Criterion {
+        self.candle_average(rule, value, setting, Comparison::Greater, i)
+    }
+
+    fn below_average(
+        &self,
+        rule: &'static str,
+        value: f64,
+        setting: CandleSetting,
+        i: usize,
+    ) -> Criterion {
+        let comparison = match setting {
+            CandleSetting::BodyDoji | CandleSetting::BodyShort => Comparison::LessOrEqual,
+            _ => Comparison::Less,
+        };
+
+        self.candle_average(rule, value, setting, comparison, i)
+    }
+
+    /// A criterion comparing two prices, scored relative to the average real body at `i`.
+    fn price(
+        &self,
+        rule: &'static str,
+        value: f64,
+        threshold: f64,
+        comparison: Comparison,
+        i: usize,
+    ) -> Criterion {
+        Criterion {
+            rule,
+            value,
+            threshold,
+            average: None,
+            comparison,
+            scale: Some(self.average(CandleSetting::BodyLong, i)),
+        }
+    }
+
+    fn color(&self, rule: &'static str, i: usize, white: bool) -> Criterion {
+        let color = if self.close(i) >= self.open(i) {
+            1.0
+        } else {
+            -1.0
+        };
+
+        Criterion {
+            rule,
+            value: color,
+            threshold: 0.0,
+            average: None,
+            comparison: if white {
+                Comparison::Greater
+            } else {
+                Comparison::Less
+            },
+            scale: None,
+        }
+    }
+
+    fn direction(&self, i: usize, white: bool) -> Criterion {
+        self.color(if white { "white" } else { "black" }, i, white)
+    }
+
+    fn body_short(&self, i: usize) -> Criterion {
+        self.candle_average(
+            "real body short",
+            self.real_body(i),
+            CandleSetting::BodyShort,
+            Comparison::Less,
+            i,
+        )
+    }
+
+    fn body_long(&self, i: usize) -> Criterion {
+        self.above_average(
+            "real body long",
+            self.real_body(i),
+            CandleSetting::BodyLong,
+            i,
+        )
+    }
+
+    fn body_doji(&self, i: usize) -> Criterion {
+        self.below_average(
+            "real body doji",
+            self.real_body(i),
+            CandleSetting::BodyDoji,
+            i,
+        )
+    }
+
+    fn upper_shadow_short(&self, i: usize) -> Criterion {
+        self.below_average(
+            "upper shadow short",
+            self.upper_shadow(i),
+            CandleSetting::ShadowShort,
+            i,
+        )
+    }
+
+    fn lower_shadow_short(&self, i: usize) -> Criterion {
+        self.below_average(
+            "lower shadow short",
+            self.lower_shadow(i),
+            CandleSetting::ShadowShort,
+            i,
+        )
+    }
+
+    fn upper_shadow_very_short(&self, i: usize) -> Criterion {
+        self.below_average(
+            "upper shadow very short",
+            self.upper_shadow(i),
+            CandleSetting::ShadowVeryShort,
+            i,
+        )
+    }
+
+    fn lower_shadow_very_short(&self, i: usize) -> Criterion {
+        self.below_average(
+            "lower shadow very short",
+            self.lower_shadow(i),
+            CandleSetting::ShadowVeryShort,
+            i,
+        )
+    }
+}